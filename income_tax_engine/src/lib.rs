@@ -6,6 +6,8 @@ use simple_money::*;
 use rust_decimal_macros::*;
 use thiserror::Error;
 use std::ops::Add;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Error, PartialEq)]
 pub enum TaxError {
@@ -22,10 +24,11 @@ pub enum TaxError {
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TaxBracket{
     min_money: Money,
     max_money: Option<Money>,
-    rate: Decimal,
+    rate: Rate,
 }
 
 impl PartialOrd for TaxBracket {
@@ -44,7 +47,7 @@ impl TaxBracket {
     fn new_tax_bracket_with_max(
         min_money: Money,
         max_money: Money,
-        rate: Decimal,
+        rate: Rate,
     ) -> Result<TaxBracket, TaxError> {
         if min_money.currency != max_money.currency {
             Err(TaxError::MismatchedCurrencies)
@@ -60,7 +63,7 @@ impl TaxBracket {
     pub fn new(
         min_money: Money,
         max_money: Option<Money>,
-        rate: Decimal,
+        rate: Rate,
     ) -> Result<TaxBracket, TaxError> {
         if let Some(max_money) = max_money {
             Self::new_tax_bracket_with_max(min_money, max_money, rate)
@@ -87,6 +90,7 @@ impl TaxBracket {
 }
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ClaimStrategy {
     ExactAmount(Money),
     Range(Money, Money),
@@ -114,6 +118,7 @@ impl ClaimStrategy {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TaxCreditRule{
     pub refundable: bool,
     pub tax_credit_identifier: String,
@@ -137,6 +142,7 @@ pub struct TaxCreditClaim{
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TaxDeductionRule {
     pub tax_deduction_identifier: String,
     pub claim_strategy: ClaimStrategy,
@@ -159,13 +165,14 @@ pub struct TaxDeductionClaim {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TaxSchedule {
     identifier: String,
     brackets: Vec<TaxBracket>,
     deductions_map: HashMap<String, TaxDeductionRule>,
     credits_map: HashMap<String, TaxCreditRule>,
     tax_currency: Currency,
-    capital_gains_inclusion_rate: Decimal,
+    capital_gains_inclusion_rate: Rate,
 }
 
 #[derive(Clone,Copy)]
@@ -191,6 +198,7 @@ impl Income {
 }
 
 #[derive(Clone,Copy,Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum TaxCalculation {
     Refund(Money),
     Liability(Money),
@@ -304,7 +312,7 @@ impl TaxSchedule {
         }
     }
 
-    pub fn determine_marginal_rate(&self, incomes: Vec<Income>, tax_deduction_claims: Vec<TaxDeductionClaim>) -> Result<Decimal, TaxError> {
+    pub fn determine_marginal_rate(&self, incomes: Vec<Income>, tax_deduction_claims: Vec<TaxDeductionClaim>) -> Result<Rate, TaxError> {
         let income_to_consider = self.determine_income_to_consider(incomes);
         let taxable_income = self.determine_taxable_income(income_to_consider, tax_deduction_claims)?;
 
@@ -337,7 +345,7 @@ impl TaxSchedule {
         identifier: &str,
         brackets: Vec<TaxBracket>,
         currency: Currency,
-        capital_gains_inclusion_rate: Decimal,
+        capital_gains_inclusion_rate: Rate,
     ) -> Result<TaxSchedule, TaxError> {
         if !Self::validate_currency_on_brackets(brackets.clone(), currency){
            Err(TaxError::MismatchedCurrencies) 
@@ -391,6 +399,7 @@ impl TaxSchedule {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TaxRegime {
     schedules: Vec<TaxSchedule>,
 }
@@ -422,8 +431,8 @@ impl<'a> FromIterator<&'a TaxCreditClaim> for Vec<TaxCreditClaim>{
 pub struct TaxRegimeCalculationResult {
     schedule_results: HashMap<String, TaxCalculation>,
     total_result: TaxCalculation,
-    average_tax_rate: Decimal,
-    marginal_tax_rate: Decimal,
+    average_tax_rate: Rate,
+    marginal_tax_rate: Rate,
 }
 
 impl TaxRegime {
@@ -455,7 +464,7 @@ impl TaxRegime {
         let currency = self.currency().unwrap();
 
         let mut tax_calculation_results: HashMap<String, TaxCalculation> = HashMap::new();
-        let mut marginal_rates: Vec<Decimal> = vec![];
+        let mut marginal_rates: Vec<Rate> = vec![];
 
         for schedule in self.schedules.clone() {
             let valid_deduction_claims_for_schedule = self.construct_deduction_claims_for_schedule(&tax_deduction_claims, &schedule);
@@ -478,7 +487,7 @@ impl TaxRegime {
         Ok(TaxRegimeCalculationResult {
             schedule_results: tax_calculation_results,
             total_result: tax_calculation_result,
-            average_tax_rate: tax_calculation_result.abs() / total_income,
+            average_tax_rate: Rate::from_fraction(tax_calculation_result.abs() / total_income),
             marginal_tax_rate: marginal_rate,
         })
     }
@@ -488,25 +497,37 @@ impl TaxRegime {
 mod tests {
     use super::*;
 
+    macro_rules! cad_money {
+        ($amount:literal) => {
+            init_cad_money(dec!($amount))
+        };
+    }
+
+    macro_rules! usd_money {
+        ($amount:literal) => {
+            init_usd_money(dec!($amount))
+        };
+    }
+
     #[test]
     fn calculate_tax_without_deductions_and_credits_with_three_brackets() {
         let lowest = TaxBracket {
             min_money: cad_money!(0),
             max_money: Some(cad_money!(10_000)),
-            rate: dec!(0.1),
+            rate: Rate::from_percent(dec!(10)),
         };
         let middle = TaxBracket {
             min_money: cad_money!(10_000),
             max_money: Some(cad_money!(20_000)),
-            rate: dec!(0.2),
+            rate: Rate::from_percent(dec!(20)),
         };
         let highest = TaxBracket {
             min_money: cad_money!(20_000),
             max_money: None,
-            rate: dec!(0.3),
+            rate: Rate::from_percent(dec!(30)),
         };
 
-        let schedule = TaxSchedule::new("TEST", vec![lowest, middle, highest], Currency::CAD, dec!(0.5)).unwrap();
+        let schedule = TaxSchedule::new("TEST", vec![lowest, middle, highest], Currency::CAD, Rate::from_percent(dec!(50))).unwrap();
 
         let twenty_five_thousand_employment_income = Income::Employment(cad_money!(25_000));
         let fifteen_thousand_employment_income = Income::Employment(cad_money!(15_000));
@@ -531,10 +552,10 @@ mod tests {
         let lowest = TaxBracket {
             min_money: cad_money!(0),
             max_money: Some(cad_money!(10_000)),
-            rate: dec!(0.1),
+            rate: Rate::from_percent(dec!(10)),
         };
 
-        let schedule = TaxSchedule::new("TEST", vec![lowest], Currency::CAD, dec!(0.5)).unwrap();
+        let schedule = TaxSchedule::new("TEST", vec![lowest], Currency::CAD, Rate::from_percent(dec!(50))).unwrap();
         let employment_income = Income::Employment(cad_money!(10_000));
         let capital_gains = Income::CapitalGains(cad_money!(10_000));
 
@@ -550,10 +571,10 @@ mod tests {
         let lowest = TaxBracket {
             min_money: cad_money!(0),
             max_money: None,
-            rate: dec!(0.1),
+            rate: Rate::from_percent(dec!(10)),
         };
 
-        let schedule = TaxSchedule::new("TEST", vec![lowest], Currency::CAD, dec!(0.5)).unwrap();
+        let schedule = TaxSchedule::new("TEST", vec![lowest], Currency::CAD, Rate::from_percent(dec!(50))).unwrap();
         let employment_income = Income::Employment(cad_money!(10_000));
         let capital_gains = Income::CapitalGains(cad_money!(10_000));
 
@@ -569,7 +590,7 @@ mod tests {
         let invalid = TaxBracket::new(
             cad_money!(0), 
             Some(usd_money!(1)), 
-            dec!(0.1)
+            Rate::from_percent(dec!(10))
         ).unwrap_err();
 
         assert_eq!(invalid, TaxError::MismatchedCurrencies);
@@ -577,13 +598,13 @@ mod tests {
         let valid_bracket = TaxBracket::new(
             cad_money!(0),
             None,
-            dec!(0.1)
+            Rate::from_percent(dec!(10))
         ).unwrap();
         let invalid_schedule = TaxSchedule::new(
             "TEST",
             vec![valid_bracket],
             Currency::USD,
-            dec!(0.5),
+            Rate::from_percent(dec!(50)),
         ).unwrap_err();
 
         assert_eq!(invalid_schedule, TaxError::MismatchedCurrencies);
@@ -594,17 +615,17 @@ mod tests {
         let lowest = TaxBracket {
             min_money: cad_money!(0),
             max_money: Some(cad_money!(10_000)),
-            rate: dec!(0.1),
+            rate: Rate::from_percent(dec!(10)),
         };
         let middle = TaxBracket {
             min_money: cad_money!(10_000),
             max_money: Some(cad_money!(20_000)),
-            rate: dec!(0.2),
+            rate: Rate::from_percent(dec!(20)),
         };
         let highest = TaxBracket {
             min_money: cad_money!(20_000),
             max_money: None,
-            rate: dec!(0.3),
+            rate: Rate::from_percent(dec!(30)),
         };
 
         let rrsp_deduction_max = TaxDeductionRule {
@@ -628,7 +649,7 @@ mod tests {
             "TEST",
             vec![lowest, middle, highest],
             Currency::CAD,
-            dec!(0.5),
+            Rate::from_percent(dec!(50)),
         ).unwrap();
 
         schedule.add_deduction(&rrsp_deduction_max);
@@ -825,47 +846,47 @@ mod tests {
         let lowest_bracket_of_first_schedule = TaxBracket {
             min_money: cad_money!(0),
             max_money: Some(cad_money!(10_000)),
-            rate: dec!(0.1),
+            rate: Rate::from_percent(dec!(10)),
         };
         let middle_bracket_of_first_schedule = TaxBracket {
             min_money: cad_money!(10_000),
             max_money: Some(cad_money!(20_000)),
-            rate: dec!(0.2),
+            rate: Rate::from_percent(dec!(20)),
         };
         let highest_bracket_of_first_schedule = TaxBracket {
             min_money: cad_money!(20_000),
             max_money: None,
-            rate: dec!(0.3),
+            rate: Rate::from_percent(dec!(30)),
         };
 
         let mut first_schedule = TaxSchedule::new(
             "FIRST",
             vec![lowest_bracket_of_first_schedule, middle_bracket_of_first_schedule, highest_bracket_of_first_schedule],
             Currency::CAD,
-            dec!(0.5),
+            Rate::from_percent(dec!(50)),
         ).unwrap();
 
         let lowest_bracket_of_second_schedule = TaxBracket {
             min_money: cad_money!(0),
             max_money: Some(cad_money!(10_000)),
-            rate: dec!(0.2),
+            rate: Rate::from_percent(dec!(20)),
         };
         let middle_bracket_of_second_schedule = TaxBracket {
             min_money: cad_money!(10_000),
             max_money: Some(cad_money!(20_000)),
-            rate: dec!(0.3),
+            rate: Rate::from_percent(dec!(30)),
         };
         let highest_bracket_of_second_schedule = TaxBracket {
             min_money: cad_money!(20_000),
             max_money: None,
-            rate: dec!(0.4),
+            rate: Rate::from_percent(dec!(40)),
         };
 
         let mut second_schedule = TaxSchedule::new(
             "SECOND",
             vec![lowest_bracket_of_second_schedule, middle_bracket_of_second_schedule, highest_bracket_of_second_schedule],
             Currency::CAD,
-            dec!(0.75),
+            Rate::from_percent(dec!(75)),
         ).unwrap();
 
         let tax_deduction_rule = TaxDeductionRule {
@@ -883,7 +904,7 @@ mod tests {
             claim_strategy: ClaimStrategy::ExactAmount(cad_money!(1_000)),
         };
         let tax_credit_rule_only_for_first = TaxCreditRule {
-            tax_credit_identifier: String::from("TEST_ONLY_FOR_SECOND"),
+            tax_credit_identifier: String::from("TEST_ONLY_FOR_FIRST"),
             claim_strategy: ClaimStrategy::ExactAmount(cad_money!(100)),
             refundable: false,
         };
@@ -906,14 +927,14 @@ mod tests {
         let calc_result_with_cap_gains = regime.calculate_tax(vec![employment_income, capital_gains_income], vec![], vec![]).unwrap();
 
         assert_eq!(calc_result_with_no_cap_gains.total_result, TaxCalculation::Liability(cad_money!(11_500)));
-        assert_eq!(calc_result_with_no_cap_gains.marginal_tax_rate, dec!(0.7));
-        assert_eq!(calc_result_with_no_cap_gains.average_tax_rate, dec!(0.46));
+        assert_eq!(calc_result_with_no_cap_gains.marginal_tax_rate, Rate::from_percent(dec!(70)));
+        assert_eq!(calc_result_with_no_cap_gains.average_tax_rate, Rate::from_fraction(dec!(0.46)));
         assert_eq!(calc_result_with_no_cap_gains.schedule_results["FIRST"], TaxCalculation::Liability(cad_money!(4_500)));
         assert_eq!(calc_result_with_no_cap_gains.schedule_results["SECOND"], TaxCalculation::Liability(cad_money!(7_000)));
 
         assert_eq!(calc_result_with_cap_gains.total_result, TaxCalculation::Liability(cad_money!(22_750)));
-        assert_eq!(calc_result_with_cap_gains.marginal_tax_rate, dec!(0.7));
-        assert_eq!(calc_result_with_cap_gains.average_tax_rate, dec!(0.455));
+        assert_eq!(calc_result_with_cap_gains.marginal_tax_rate, Rate::from_percent(dec!(70)));
+        assert_eq!(calc_result_with_cap_gains.average_tax_rate, Rate::from_fraction(dec!(0.455)));
         assert_eq!(calc_result_with_cap_gains.schedule_results["FIRST"], TaxCalculation::Liability(cad_money!(8_250)));
         assert_eq!(calc_result_with_cap_gains.schedule_results["SECOND"], TaxCalculation::Liability(cad_money!(14_500)));
 
@@ -924,14 +945,14 @@ mod tests {
         let calc_result_with_low_income = regime.calculate_tax(vec![low_employment_income], vec![], vec![]).unwrap();
 
         assert_eq!(calc_result_with_middle_income.total_result, TaxCalculation::Liability(cad_money!(5_500)));
-        assert_eq!(calc_result_with_middle_income.marginal_tax_rate, dec!(0.5));
-        assert_eq!(calc_result_with_middle_income.average_tax_rate, dec!(5_500) / dec!(15_000));
+        assert_eq!(calc_result_with_middle_income.marginal_tax_rate, Rate::from_percent(dec!(50)));
+        assert_eq!(calc_result_with_middle_income.average_tax_rate, Rate::from_fraction(dec!(5_500) / dec!(15_000)));
         assert_eq!(calc_result_with_middle_income.schedule_results["FIRST"], TaxCalculation::Liability(cad_money!(2_000)));
         assert_eq!(calc_result_with_middle_income.schedule_results["SECOND"], TaxCalculation::Liability(cad_money!(3_500)));
 
         assert_eq!(calc_result_with_low_income.total_result, TaxCalculation::Liability(cad_money!(1_500)));
-        assert_eq!(calc_result_with_low_income.marginal_tax_rate, dec!(0.3));
-        assert_eq!(calc_result_with_low_income.average_tax_rate, dec!(1_500) / dec!(5_000));
+        assert_eq!(calc_result_with_low_income.marginal_tax_rate, Rate::from_percent(dec!(30)));
+        assert_eq!(calc_result_with_low_income.average_tax_rate, Rate::from_fraction(dec!(1_500) / dec!(5_000)));
         assert_eq!(calc_result_with_low_income.schedule_results["FIRST"], TaxCalculation::Liability(cad_money!(500)));
         assert_eq!(calc_result_with_low_income.schedule_results["SECOND"], TaxCalculation::Liability(cad_money!(1_000)));
 
@@ -970,22 +991,22 @@ mod tests {
         ).unwrap();
 
         assert_eq!(calc_result_with_deduction.total_result, TaxCalculation::Liability(cad_money!(3_000)));
-        assert_eq!(calc_result_with_deduction.marginal_tax_rate, dec!(0.3));
-        assert_eq!(calc_result_with_deduction.average_tax_rate, dec!(3_000) / dec!(15_000));
+        assert_eq!(calc_result_with_deduction.marginal_tax_rate, Rate::from_percent(dec!(30)));
+        assert_eq!(calc_result_with_deduction.average_tax_rate, Rate::from_fraction(dec!(3_000) / dec!(15_000)));
         assert_eq!(calc_result_with_deduction.schedule_results["FIRST"], TaxCalculation::Liability(cad_money!(1_000)));
         assert_eq!(calc_result_with_deduction.schedule_results["SECOND"], TaxCalculation::Liability(cad_money!(2_000)));
 
         // This is a bit confusing but because the credit applies to both schedules, it gets applied twice
         // So: 10_000 * 0.1 + 5_000 * 0.2 - 500 + 10_000 * 0.2 + 5_000 * 0.3 - 500
         assert_eq!(calc_result_with_credit.total_result, TaxCalculation::Liability(cad_money!(4_500)));
-        assert_eq!(calc_result_with_credit.marginal_tax_rate, dec!(0.5));
-        assert_eq!(calc_result_with_credit.average_tax_rate, dec!(4_500) / dec!(15_000));
+        assert_eq!(calc_result_with_credit.marginal_tax_rate, Rate::from_percent(dec!(50)));
+        assert_eq!(calc_result_with_credit.average_tax_rate, Rate::from_fraction(dec!(4_500) / dec!(15_000)));
         assert_eq!(calc_result_with_credit.schedule_results["FIRST"], TaxCalculation::Liability(cad_money!(1_500)));
         assert_eq!(calc_result_with_credit.schedule_results["SECOND"], TaxCalculation::Liability(cad_money!(3_000)));
 
         assert_eq!(calc_result_with_deduction_and_credit.total_result, TaxCalculation::Liability(cad_money!(2_000)));
-        assert_eq!(calc_result_with_deduction_and_credit.marginal_tax_rate, dec!(0.3));
-        assert_eq!(calc_result_with_deduction_and_credit.average_tax_rate, dec!(2_000) / dec!(15_000));
+        assert_eq!(calc_result_with_deduction_and_credit.marginal_tax_rate, Rate::from_percent(dec!(30)));
+        assert_eq!(calc_result_with_deduction_and_credit.average_tax_rate, Rate::from_fraction(dec!(2_000) / dec!(15_000)));
         assert_eq!(calc_result_with_deduction_and_credit.schedule_results["FIRST"], TaxCalculation::Liability(cad_money!(500)));
         assert_eq!(calc_result_with_deduction_and_credit.schedule_results["SECOND"], TaxCalculation::Liability(cad_money!(1_500)));
 
@@ -1006,22 +1027,59 @@ mod tests {
         ).unwrap();
 
         assert_eq!(calc_result_with_deduction_only_on_first_schedule.total_result, TaxCalculation::Liability(cad_money!(5_300)));
-        assert_eq!(calc_result_with_deduction_only_on_first_schedule.marginal_tax_rate, dec!(0.5));
-        assert_eq!(calc_result_with_deduction_only_on_first_schedule.average_tax_rate, dec!(0.35));
+        assert_eq!(calc_result_with_deduction_only_on_first_schedule.marginal_tax_rate, Rate::from_percent(dec!(50)));
+        assert_eq!(calc_result_with_deduction_only_on_first_schedule.average_tax_rate, Rate::from_fraction(dec!(5_300) / dec!(15_000)));
         assert_eq!(calc_result_with_deduction_only_on_first_schedule.schedule_results["FIRST"], TaxCalculation::Liability(cad_money!(1_800)));
         assert_eq!(calc_result_with_deduction_only_on_first_schedule.schedule_results["SECOND"], TaxCalculation::Liability(cad_money!(3_500)));
         
         assert_eq!(calc_result_with_credit_only_on_first_schedule.total_result, TaxCalculation::Liability(cad_money!(5_400)));
-        assert_eq!(calc_result_with_credit_only_on_first_schedule.marginal_tax_rate, dec!(0.5));
-        assert_eq!(calc_result_with_credit_only_on_first_schedule.average_tax_rate, dec!(0.36));
+        assert_eq!(calc_result_with_credit_only_on_first_schedule.marginal_tax_rate, Rate::from_percent(dec!(50)));
+        assert_eq!(calc_result_with_credit_only_on_first_schedule.average_tax_rate, Rate::from_fraction(dec!(0.36)));
         assert_eq!(calc_result_with_credit_only_on_first_schedule.schedule_results["FIRST"], TaxCalculation::Liability(cad_money!(1_900)));
         assert_eq!(calc_result_with_credit_only_on_first_schedule.schedule_results["SECOND"], TaxCalculation::Liability(cad_money!(3_500)));
 
         assert_eq!(calc_result_with_deduction_and_credit_only_on_first_schedule.total_result, TaxCalculation::Liability(cad_money!(5_200)));
-        assert_eq!(calc_result_with_deduction_and_credit_only_on_first_schedule.marginal_tax_rate, dec!(0.5));
-        assert_eq!(calc_result_with_credit_only_on_first_schedule.average_tax_rate, dec!(0.34));
+        assert_eq!(calc_result_with_deduction_and_credit_only_on_first_schedule.marginal_tax_rate, Rate::from_percent(dec!(50)));
+        assert_eq!(calc_result_with_deduction_and_credit_only_on_first_schedule.average_tax_rate, Rate::from_fraction(dec!(5_200) / dec!(15_000)));
         assert_eq!(calc_result_with_deduction_and_credit_only_on_first_schedule.schedule_results["FIRST"], TaxCalculation::Liability(cad_money!(1_700)));
         assert_eq!(calc_result_with_deduction_and_credit_only_on_first_schedule.schedule_results["SECOND"], TaxCalculation::Liability(cad_money!(3_500)));
 
     }
-}
\ No newline at end of file
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn schedule_and_calculation_round_trip_through_json() {
+        let lowest = TaxBracket::new(cad_money!(0), Some(cad_money!(10_000)), Rate::from_percent(dec!(10))).unwrap();
+        let highest = TaxBracket::new(cad_money!(10_000), None, Rate::from_percent(dec!(33.33))).unwrap();
+        let mut schedule = TaxSchedule::new("TEST", vec![lowest, highest], Currency::CAD, Rate::from_percent(dec!(50))).unwrap();
+        schedule.add_deduction(&TaxDeductionRule {
+            tax_deduction_identifier: "RRSP".to_string(),
+            claim_strategy: ClaimStrategy::Range(cad_money!(0), cad_money!(5_000)),
+        });
+        schedule.add_credit(&TaxCreditRule {
+            refundable: false,
+            tax_credit_identifier: "BPA".to_string(),
+            claim_strategy: ClaimStrategy::ExactAmount(cad_money!(1_234.56)),
+        });
+        let mut regime = TaxRegime::new();
+        regime.add_schedule(schedule.clone());
+
+        let schedule_json = serde_json::to_string(&schedule).unwrap();
+        let deserialized_schedule: TaxSchedule = serde_json::from_str(&schedule_json).unwrap();
+        assert_eq!(deserialized_schedule.brackets, schedule.brackets);
+        assert_eq!(deserialized_schedule.capital_gains_inclusion_rate, Rate::from_percent(dec!(50)));
+        assert_eq!(deserialized_schedule.tax_currency, Currency::CAD);
+        assert!(deserialized_schedule.deductions_map.contains_key("RRSP"));
+        assert!(deserialized_schedule.credits_map.contains_key("BPA"));
+
+        let regime_json = serde_json::to_string(&regime).unwrap();
+        let deserialized_regime: TaxRegime = serde_json::from_str(&regime_json).unwrap();
+        assert_eq!(deserialized_regime.currency(), Some(Currency::CAD));
+
+        let incomes = vec![Income::Employment(cad_money!(25_000))];
+        let calculation = schedule.calculate_tax_result(incomes.clone(), vec![], vec![]).unwrap();
+        let deserialized_calculation: TaxCalculation = serde_json::from_str(&serde_json::to_string(&calculation).unwrap()).unwrap();
+        assert_eq!(deserialized_calculation, calculation);
+        assert_eq!(deserialized_schedule.calculate_tax_result(incomes, vec![], vec![]).unwrap(), calculation);
+    }
+}
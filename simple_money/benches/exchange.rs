@@ -0,0 +1,116 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rust_decimal::Decimal;
+use simple_money::{Currency, Exchange, ExchangeSnapshot, Money};
+use std::collections::HashMap;
+use std::thread;
+
+const CONVERSIONS: i64 = 10_000;
+const THREADS: usize = 4;
+
+/// An exchange with every currency quoted against USD, so conversions between any other two
+/// currencies are triangulated.
+fn exchange() -> Exchange {
+    let mut exchange = Exchange::new();
+    for (index, currency) in Currency::all().iter().enumerate() {
+        if *currency != Currency::USD {
+            exchange.set_rate_and_inverse(Currency::USD, *currency, Decimal::new(index as i64 + 50, 2));
+        }
+    }
+    exchange
+}
+
+/// The key `Exchange` hashed to look up rates before it kept them in matrices.
+#[derive(PartialEq, Eq, Hash)]
+struct RateQuery {
+    from: Currency,
+    to: Currency,
+}
+
+/// The rates of `exchange()` in a `HashMap`, as `Exchange` stored them before, to compare the
+/// matrix lookups against.
+fn hashed_rates() -> HashMap<RateQuery, Decimal> {
+    let mut rates = HashMap::new();
+    for (index, currency) in Currency::all().iter().enumerate() {
+        if *currency != Currency::USD {
+            let rate = Decimal::new(index as i64 + 50, 2);
+            rates.insert(RateQuery { from: Currency::USD, to: *currency }, rate);
+            rates.insert(RateQuery { from: *currency, to: Currency::USD }, Decimal::new(1, 0) / rate);
+        }
+    }
+    rates
+}
+
+fn amounts() -> Vec<Money> {
+    let currencies = [Currency::USD, Currency::EUR, Currency::GBP, Currency::JPY];
+    (0..CONVERSIONS).map(|cents| Money { amount: Decimal::new(cents * 37, 2), currency: currencies[cents as usize % currencies.len()] }).collect()
+}
+
+fn get_rate(c: &mut Criterion) {
+    let exchange = exchange();
+    let snapshot = exchange.snapshot();
+    let hashed_rates = hashed_rates();
+    let mut group = c.benchmark_group("get_rate");
+
+    group.bench_function("hashmap_direct", |b| {
+        b.iter(|| *hashed_rates.get(&RateQuery { from: black_box(Currency::USD), to: black_box(Currency::CAD) }).unwrap())
+    });
+    group.bench_function("exchange_direct", |b| b.iter(|| exchange.get_rate(black_box(Currency::USD), black_box(Currency::CAD)).unwrap()));
+    group.bench_function("exchange_triangulated", |b| b.iter(|| exchange.get_rate(black_box(Currency::EUR), black_box(Currency::CAD)).unwrap()));
+    group.bench_function("snapshot_direct", |b| b.iter(|| snapshot.get_rate(black_box(Currency::USD), black_box(Currency::CAD)).unwrap()));
+    group.bench_function("snapshot_triangulated", |b| b.iter(|| snapshot.get_rate(black_box(Currency::EUR), black_box(Currency::CAD)).unwrap()));
+    group.finish();
+}
+
+fn convert(c: &mut Criterion) {
+    let exchange = exchange();
+    let snapshot = exchange.snapshot();
+    let amounts = amounts();
+    let mut group = c.benchmark_group("convert");
+
+    group.bench_function("exchange", |b| {
+        b.iter(|| black_box(&amounts).iter().map(|money| exchange.convert(*money, Currency::CAD).unwrap().converted).collect::<Vec<Money>>())
+    });
+    group.bench_function("snapshot", |b| {
+        b.iter(|| black_box(&amounts).iter().map(|money| snapshot.convert(*money, Currency::CAD).unwrap().converted).collect::<Vec<Money>>())
+    });
+    group.finish();
+}
+
+fn convert_in_threads(c: &mut Criterion) {
+    let exchange = exchange();
+    let snapshot = exchange.snapshot();
+    let amounts = amounts();
+    let mut group = c.benchmark_group("convert_in_threads");
+
+    group.bench_function("exchange", |b| {
+        b.iter(|| {
+            thread::scope(|scope| {
+                for _ in 0..THREADS {
+                    scope.spawn(|| black_box(&amounts).iter().map(|money| exchange.convert(*money, Currency::CAD).unwrap().converted).collect::<Vec<Money>>());
+                }
+            })
+        })
+    });
+    group.bench_function("snapshot", |b| {
+        b.iter(|| {
+            thread::scope(|scope| {
+                for _ in 0..THREADS {
+                    scope.spawn(|| black_box(&amounts).iter().map(|money| snapshot.convert(*money, Currency::CAD).unwrap().converted).collect::<Vec<Money>>());
+                }
+            })
+        })
+    });
+    group.finish();
+}
+
+fn take_snapshot(c: &mut Criterion) {
+    let exchange = exchange();
+    let mut group = c.benchmark_group("snapshot");
+
+    group.sample_size(10);
+    group.bench_function("all_currencies", |b| b.iter(|| -> ExchangeSnapshot { black_box(&exchange).snapshot() }));
+    group.finish();
+}
+
+criterion_group!(benches, get_rate, convert, convert_in_threads, take_snapshot);
+criterion_main!(benches);
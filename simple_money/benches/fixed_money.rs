@@ -0,0 +1,58 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rust_decimal::Decimal;
+use simple_money::{init_zero_amount, Currency, FixedMoney, Money};
+
+const AMOUNTS: i64 = 10_000;
+
+fn decimal_amounts() -> Vec<Money> {
+    (1..=AMOUNTS).map(|cents| Money { amount: Decimal::new(cents * 37, 2), currency: Currency::USD }).collect()
+}
+
+fn fixed_amounts() -> Vec<FixedMoney> {
+    (1..=AMOUNTS).map(|cents| FixedMoney::new(cents * 37, Currency::USD)).collect()
+}
+
+fn sum(c: &mut Criterion) {
+    let decimal = decimal_amounts();
+    let fixed = fixed_amounts();
+    let mut group = c.benchmark_group("sum");
+
+    group.bench_function("decimal", |b| {
+        b.iter(|| black_box(&decimal).iter().fold(init_zero_amount(Currency::USD), |total, money| total + *money))
+    });
+    group.bench_function("fixed", |b| {
+        b.iter(|| FixedMoney::checked_sum(Currency::USD, black_box(&fixed).iter().copied()).unwrap())
+    });
+    group.finish();
+}
+
+fn scale(c: &mut Criterion) {
+    let decimal = decimal_amounts();
+    let fixed = fixed_amounts();
+    let mut group = c.benchmark_group("scale");
+
+    group.bench_function("decimal", |b| {
+        b.iter(|| black_box(&decimal).iter().map(|money| *money * 3_i64).collect::<Vec<Money>>())
+    });
+    group.bench_function("fixed", |b| {
+        b.iter(|| black_box(&fixed).iter().map(|money| money.checked_mul(3).unwrap()).collect::<Vec<FixedMoney>>())
+    });
+    group.finish();
+}
+
+fn convert(c: &mut Criterion) {
+    let decimal = decimal_amounts();
+    let fixed = fixed_amounts();
+    let mut group = c.benchmark_group("convert");
+
+    group.bench_function("to_fixed", |b| {
+        b.iter(|| black_box(&decimal).iter().map(|money| FixedMoney::from_money(*money).unwrap()).collect::<Vec<FixedMoney>>())
+    });
+    group.bench_function("to_decimal", |b| {
+        b.iter(|| black_box(&fixed).iter().map(FixedMoney::to_money).collect::<Vec<Money>>())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, sum, scale, convert);
+criterion_main!(benches);
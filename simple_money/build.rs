@@ -0,0 +1,31 @@
+#[path = "src/currency_table.rs"]
+mod currency_table;
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/currency_table.rs");
+    println!("cargo:rerun-if-changed=data/currencies.csv");
+    println!("cargo:rerun-if-changed=data/changeovers.csv");
+
+    let data = fs::read_to_string("data/currencies.csv").expect("could not read data/currencies.csv");
+    let records = match currency_table::parse(&data) {
+        Ok(records) => records,
+        Err(error) => panic!("data/currencies.csv is invalid: {}", error),
+    };
+
+    let data = fs::read_to_string("data/changeovers.csv").expect("could not read data/changeovers.csv");
+    let changeovers = match currency_table::parse_changeovers(&data, &records) {
+        Ok(changeovers) => changeovers,
+        Err(error) => panic!("data/changeovers.csv is invalid: {}", error),
+    };
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is not set");
+    let out_dir = Path::new(&out_dir);
+    fs::write(out_dir.join("currencies.rs"), currency_table::generate(&records)).expect("could not write the currency table");
+    fs::write(out_dir.join("currency_types.rs"), currency_table::generate_currency_types(&records)).expect("could not write the currency types");
+    fs::write(out_dir.join("changeovers.rs"), currency_table::generate_changeovers(&changeovers)).expect("could not write the changeover table");
+}
@@ -0,0 +1,113 @@
+use crate::currency::ALL_CURRENCIES;
+use crate::Currency;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal_macros::*;
+use std::sync::OnceLock;
+
+/// The official, irrevocably fixed conversion from a withdrawn currency into the currency that
+/// replaced it.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Changeover {
+    pub from: Currency,
+    pub to: Currency,
+    /// The first day on which `to` replaced `from`.
+    pub date: NaiveDate,
+    /// How many units of `from` make one unit of `to`.
+    pub factor: Decimal,
+}
+
+struct ChangeoverData {
+    from: Currency,
+    to: Currency,
+    date: (i32, u32, u32),
+    factor: Decimal,
+}
+
+impl ChangeoverData {
+    fn changeover(&self) -> Changeover {
+        let (year, month, day) = self.date;
+        Changeover {
+            from: self.from,
+            to: self.to,
+            date: NaiveDate::from_ymd_opt(year, month, day).unwrap(),
+            factor: self.factor,
+        }
+    }
+}
+
+// `CHANGEOVERS`, a `ChangeoverData` for each row of data/changeovers.csv, is generated by
+// build.rs.
+include!(concat!(env!("OUT_DIR"), "/changeovers.rs"));
+
+/// The changeovers from and to each currency, indexed by `Currency::index`. Custom currencies
+/// are past the end, since they never have changeovers.
+struct ChangeoverIndex {
+    replaced_by: Vec<Option<Changeover>>,
+    predecessors: Vec<Vec<Changeover>>,
+}
+
+static CHANGEOVER_INDEX: OnceLock<ChangeoverIndex> = OnceLock::new();
+
+fn changeover_index() -> &'static ChangeoverIndex {
+    CHANGEOVER_INDEX.get_or_init(|| {
+        let mut index = ChangeoverIndex {
+            replaced_by: vec![None; ALL_CURRENCIES.len()],
+            predecessors: vec![Vec::new(); ALL_CURRENCIES.len()],
+        };
+        for changeover in CHANGEOVERS.iter().map(ChangeoverData::changeover) {
+            index.replaced_by[changeover.from.index()] = Some(changeover);
+            index.predecessors[changeover.to.index()].push(changeover);
+        }
+        for predecessors in &mut index.predecessors {
+            predecessors.sort_by_key(|changeover| changeover.date);
+        }
+
+        index
+    })
+}
+
+impl Currency {
+    /// Returns how this currency was replaced, if it has been withdrawn.
+    pub fn changeover(&self) -> Option<Changeover> {
+        changeover_index().replaced_by.get(self.index()).copied().flatten()
+    }
+
+    /// Returns every changeover that replaced another currency with this one, earliest first.
+    pub fn predecessors(&self) -> Vec<Changeover> {
+        changeover_index().predecessors.get(self.index()).cloned().unwrap_or_default()
+    }
+
+    /// The first day this currency is no longer in use, if it has been withdrawn.
+    pub fn withdrawn_on(&self) -> Option<NaiveDate> {
+        self.changeover().map(|changeover| changeover.date)
+    }
+
+    /// The day this currency was introduced, if it replaced another currency.
+    pub fn introduced_on(&self) -> Option<NaiveDate> {
+        self.predecessors().first().map(|changeover| changeover.date)
+    }
+
+    pub fn is_obsolete(&self) -> bool {
+        self.changeover().is_some()
+    }
+
+    /// Whether this currency was in use on `date`, as far as its changeovers tell.
+    pub fn is_valid_on(&self, date: NaiveDate) -> bool {
+        !matches!(self.introduced_on(), Some(introduced_on) if date < introduced_on)
+            && !matches!(self.withdrawn_on(), Some(withdrawn_on) if withdrawn_on <= date)
+    }
+
+    /// Follows this currency's changeovers up to `date`, returning the currency in use on that
+    /// date and how many units of this currency make one unit of it.
+    pub fn successor_on(&self, date: NaiveDate) -> (Currency, Decimal) {
+        let mut currency = *self;
+        let mut factor = dec!(1);
+        while let Some(changeover) = currency.changeover().filter(|changeover| changeover.date <= date) {
+            currency = changeover.to;
+            factor *= changeover.factor;
+        }
+
+        (currency, factor)
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{init_zero_amount, CheckedSub, Currency, Exchange, Money, MoneyError};
+use rust_decimal::Decimal;
+use rust_decimal_macros::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// What it costs to convert along one currency pair, on top of the mid rate.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ConversionCosts {
+    /// The gap between the ask and bid rates as a fraction of the mid rate. Conversions are made
+    /// at the bid rate, which sits half of the spread below the mid rate.
+    pub spread: Decimal,
+    /// A fee charged as a fraction of the amount received after the spread.
+    pub percentage_fee: Decimal,
+    /// A flat fee in either the source or the target currency of the pair.
+    pub fixed_fee: Option<Money>,
+}
+
+/// The costs charged by a single conversion, all expressed in the target currency.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct FeeBreakdown {
+    pub spread: Money,
+    pub percentage_fee: Money,
+    pub fixed_fee: Money,
+}
+
+impl FeeBreakdown {
+    pub fn none(currency: Currency) -> FeeBreakdown {
+        FeeBreakdown {
+            spread: init_zero_amount(currency),
+            percentage_fee: init_zero_amount(currency),
+            fixed_fee: init_zero_amount(currency),
+        }
+    }
+
+    pub fn total(&self) -> Money {
+        self.spread + self.percentage_fee + self.fixed_fee
+    }
+}
+
+/// The result of `Exchange::convert`: the amount received and what was lost along the way.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Conversion {
+    pub converted: Money,
+    pub fees: FeeBreakdown,
+}
+
+impl Exchange {
+    /// Sets the spread and fees charged when converting from `from` to `to`.
+    ///
+    /// The fixed fee, if any, must be in one of the two currencies. A fee in `from` is taken
+    /// before converting and a fee in `to` is taken from the converted amount. None of the
+    /// costs can be negative.
+    pub fn set_conversion_costs(&mut self, from: Currency, to: Currency, costs: ConversionCosts) -> Result<(), MoneyError> {
+        if let Some(fixed_fee) = costs.fixed_fee {
+            if fixed_fee.currency != from && fixed_fee.currency != to {
+                return Err(MoneyError::MismatchedCurrencies);
+            }
+            if fixed_fee.amount.is_sign_negative() {
+                return Err(MoneyError::InvalidConversionCosts(format!("the {}/{} fixed fee can't be negative", from, to)));
+            }
+        }
+        if costs.spread.is_sign_negative() || costs.percentage_fee.is_sign_negative() {
+            return Err(MoneyError::InvalidConversionCosts(format!("the {}/{} spread and percentage fee can't be negative", from, to)));
+        }
+
+        self.costs.insert(from, to, costs);
+        Ok(())
+    }
+
+    pub fn conversion_costs(&self, from: Currency, to: Currency) -> ConversionCosts {
+        self.costs.get(from, to).copied().unwrap_or_default()
+    }
+
+    /// Converts `money` at `rate` and charges the pair's costs, failing with
+    /// `MoneyError::FeesExceedAmount` when they come to more than the converted amount.
+    pub(crate) fn convert_with_costs(&self, money: Money, currency: Currency, rate: Decimal) -> Result<Conversion, MoneyError> {
+        let costs = self.conversion_costs(money.currency, currency);
+        let mid_value = self.convert_at_rate(money, currency, rate)?;
+
+        let (source_after_fixed_fee, fixed_fee) = match costs.fixed_fee {
+            Some(fixed_fee) if fixed_fee.currency == money.currency => {
+                let fixed_fee_value = fixed_fee.amount.checked_mul(rate).ok_or(MoneyError::Overflow)?;
+                (money.amount.checked_sub(fixed_fee.amount).ok_or(MoneyError::Overflow)?, self.round(Money { amount: fixed_fee_value, currency }))
+            }
+            Some(fixed_fee) => (money.amount, fixed_fee),
+            None => (money.amount, init_zero_amount(currency)),
+        };
+
+        let value_after_fixed_fee = source_after_fixed_fee.checked_mul(rate).ok_or(MoneyError::Overflow)?;
+        let spread = value_after_fixed_fee.checked_mul(costs.spread).ok_or(MoneyError::Overflow)? / dec!(2);
+        let percentage_fee = (value_after_fixed_fee - spread).checked_mul(costs.percentage_fee).ok_or(MoneyError::Overflow)?;
+
+        let fees = FeeBreakdown {
+            spread: self.round(Money { amount: spread, currency }),
+            percentage_fee: self.round(Money { amount: percentage_fee, currency }),
+            fixed_fee,
+        };
+        let total_fees = Money::checked_sum(currency, [fees.spread, fees.percentage_fee, fees.fixed_fee])?;
+        let converted = mid_value.checked_sub(total_fees)?;
+        if converted.amount.is_sign_negative() && !mid_value.amount.is_sign_negative() {
+            return Err(MoneyError::FeesExceedAmount(total_fees));
+        }
+
+        Ok(Conversion { converted, fees })
+    }
+}
@@ -0,0 +1,292 @@
+// Reads and checks data/currencies.csv and data/changeovers.csv. build.rs uses this to generate
+// the `Currency` enum and its data, the typed currency markers and the changeover table, and the
+// crate's tests use it to exercise the rules below. It can't depend on the rest of the crate,
+// since build.rs includes it on its own.
+
+use std::collections::HashMap;
+
+const HEADER: &str = "code,numeric_code,name,exponent,cash_increment,symbol,symbol_first,locale";
+
+const CHANGEOVER_HEADER: &str = "from,to,date,factor";
+
+/// The variants of `Locale`.
+const LOCALES: [&str; 7] = ["USA", "EU", "India", "Poland", "Switzerland", "France", "Arabic"];
+
+/// No ISO 4217 currency has more than 4 decimal places.
+const MAX_EXPONENT: u8 = 4;
+
+/// One row of the currency table.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct CurrencyRecord {
+    pub code: String,
+    pub numeric_code: u16,
+    pub name: String,
+    pub exponent: u8,
+    pub cash_increment: u16,
+    pub symbol: String,
+    pub symbol_first: bool,
+    pub locale: String,
+}
+
+/// Parses the currency table and checks every row, skipping blank lines and lines starting
+/// with `#`. Errors give the line they were found on.
+///
+/// Every field must be filled in. Codes are three upper case letters in alphabetical order, and
+/// no two currencies may share a code, numeric code or name. Symbols may be shared, since many
+/// currencies use `$` or `£`.
+pub fn parse(data: &str) -> Result<Vec<CurrencyRecord>, String> {
+    let lines = rows(data, HEADER, "currency")?;
+
+    let mut records: Vec<CurrencyRecord> = Vec::new();
+    let mut numeric_codes: HashMap<u16, String> = HashMap::new();
+    let mut names: HashMap<String, String> = HashMap::new();
+    for (line, text) in lines {
+        let record = parse_record(text).map_err(|error| format!("line {}: {}", line, error))?;
+
+        if let Some(previous) = records.last() {
+            if previous.code == record.code {
+                return Err(format!("line {}: {} is listed twice", line, record.code));
+            }
+            if previous.code > record.code {
+                return Err(format!("line {}: {} must come before {}", line, record.code, previous.code));
+            }
+        }
+        if let Some(code) = numeric_codes.insert(record.numeric_code, record.code.clone()) {
+            return Err(format!("line {}: numeric code {} is already used by {}", line, record.numeric_code, code));
+        }
+        if let Some(code) = names.insert(record.name.clone(), record.code.clone()) {
+            return Err(format!("line {}: the name {} is already used by {}", line, record.name, code));
+        }
+
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+/// Returns the numbered rows of a table after checking its header, skipping blank lines and
+/// lines starting with `#`.
+fn rows<'a>(data: &'a str, header: &str, table: &str) -> Result<Vec<(usize, &'a str)>, String> {
+    let mut lines = data
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim_end()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+    match lines.next() {
+        Some((_, text)) if text == header => Ok(lines.collect()),
+        Some((line, _)) => Err(format!("line {}: expected the header {}", line, header)),
+        None => Err(format!("the {} table is empty", table)),
+    }
+}
+
+fn parse_record(text: &str) -> Result<CurrencyRecord, String> {
+    let fields: Vec<&str> = text.split(',').collect();
+    if fields.len() != 8 {
+        return Err(format!("expected 8 fields but found {}", fields.len()));
+    }
+    if fields.iter().any(|field| field.is_empty() || field.trim() != *field) {
+        return Err("fields can't be empty or start or end with spaces".to_string());
+    }
+
+    let code = fields[0];
+    if code.len() != 3 || !code.chars().all(|c| c.is_ascii_uppercase()) {
+        return Err(format!("{} is not three upper case letters", code));
+    }
+
+    let numeric_code = fields[1].parse::<u16>().ok().filter(|numeric_code| (1..=999).contains(numeric_code));
+    let numeric_code = numeric_code.ok_or_else(|| format!("{} is not a numeric code from 1 to 999", fields[1]))?;
+
+    let exponent = fields[3].parse::<u8>().ok().filter(|exponent| *exponent <= MAX_EXPONENT);
+    let exponent = exponent.ok_or_else(|| format!("{} is not an exponent from 0 to {}", fields[3], MAX_EXPONENT))?;
+
+    let cash_increment = fields[4].parse::<u16>().ok().filter(|cash_increment| *cash_increment > 0);
+    let cash_increment = cash_increment.ok_or_else(|| format!("{} is not a cash increment of at least 1", fields[4]))?;
+
+    let symbol_first = match fields[6] {
+        "true" => true,
+        "false" => false,
+        other => return Err(format!("symbol_first must be true or false, not {}", other)),
+    };
+
+    if !LOCALES.contains(&fields[7]) {
+        return Err(format!("{} is not one of the locales {}", fields[7], LOCALES.join(", ")));
+    }
+
+    Ok(CurrencyRecord {
+        code: code.to_string(),
+        numeric_code,
+        name: fields[2].to_string(),
+        exponent,
+        cash_increment,
+        symbol: fields[5].to_string(),
+        symbol_first,
+        locale: fields[7].to_string(),
+    })
+}
+
+/// Writes the `CurrencyData` constants, the `Currency` enum, `ALL_CURRENCIES`,
+/// `Currency::match_currency_to_data` and `Currency::index` for `records`.
+pub fn generate(records: &[CurrencyRecord]) -> String {
+    let mut code = String::from("// Generated by build.rs from data/currencies.csv. Edit that file instead.\n");
+
+    for record in records {
+        code.push_str(&format!(
+            "\nconst {}_CURRENCY_DATA: CurrencyData = CurrencyData {{\n    code: {:?},\n    numeric_code: {},\n    name: {:?},\n    exponent: {},\n    cash_increment: {},\n    locale: Locale::{},\n    symbol: {:?},\n    symbol_first: {},\n}};\n",
+            record.code, record.code, record.numeric_code, record.name, record.exponent, record.cash_increment, record.locale, record.symbol, record.symbol_first,
+        ));
+    }
+
+    code.push_str("\n#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]\npub enum Currency {\n");
+    for record in records {
+        code.push_str(&format!("    /// {}\n    {},\n", record.name, record.code));
+    }
+    code.push_str("    /// A currency added at runtime with `Currency::register`.\n    Custom(CustomCurrency),\n}\n");
+
+    code.push_str(&format!("\npub const ALL_CURRENCIES: [Currency; {}] = [\n", records.len()));
+    for record in records {
+        code.push_str(&format!("    Currency::{},\n", record.code));
+    }
+    code.push_str("];\n");
+
+    code.push_str("\nimpl Currency {\n    pub fn match_currency_to_data(&self) -> CurrencyData {\n        match self {\n");
+    for record in records {
+        code.push_str(&format!("            Currency::{} => {}_CURRENCY_DATA,\n", record.code, record.code));
+    }
+    code.push_str("            Currency::Custom(custom_currency) => custom_currency.data(),\n        }\n    }\n");
+
+    code.push_str("\n    /// The currency's position in `ALL_CURRENCIES`, with custom currencies numbered after the\n    /// built-in ones in the order they were registered.\n    pub(crate) fn index(&self) -> usize {\n        match self {\n");
+    for (index, record) in records.iter().enumerate() {
+        code.push_str(&format!("            Currency::{} => {},\n", record.code, index));
+    }
+    code.push_str("            Currency::Custom(custom_currency) => ALL_CURRENCIES.len() + custom_currency.index(),\n        }\n    }\n}\n");
+
+    code
+}
+
+/// The name of the marker type for a currency code, such as `Cad` for `CAD`.
+fn type_name(code: &str) -> String {
+    let mut name = code[..1].to_string();
+    name.push_str(&code[1..].to_ascii_lowercase());
+    name
+}
+
+/// Writes the `currency_types!` invocation that declares a `CurrencyType` for each of `records`.
+pub fn generate_currency_types(records: &[CurrencyRecord]) -> String {
+    let mut code = String::from("// Generated by build.rs from data/currencies.csv. Edit that file instead.\n\ncurrency_types! {\n");
+    for record in records {
+        code.push_str(&format!("    {} => {},\n", type_name(&record.code), record.code));
+    }
+    code.push_str("}\n");
+
+    code
+}
+
+/// One row of the changeover table.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ChangeoverRecord {
+    pub from: String,
+    pub to: String,
+    pub date: (i32, u32, u32),
+    pub factor: String,
+}
+
+/// Parses the changeover table and checks every row against the currency table `records`,
+/// skipping blank lines and lines starting with `#`. Errors give the line they were found on.
+///
+/// Both codes must be in the currency table and differ, dates are `YYYY-MM-DD` and factors are
+/// positive decimal numbers. A currency can only be replaced once, and not before the changeover
+/// that introduced it, which also rules out cycles.
+pub fn parse_changeovers(data: &str, records: &[CurrencyRecord]) -> Result<Vec<ChangeoverRecord>, String> {
+    let lines = rows(data, CHANGEOVER_HEADER, "changeover")?;
+
+    let mut changeovers: Vec<ChangeoverRecord> = Vec::new();
+    let mut replaced: HashMap<String, usize> = HashMap::new();
+    for (line, text) in &lines {
+        let changeover = parse_changeover(text, records).map_err(|error| format!("line {}: {}", line, error))?;
+        if let Some(previous) = replaced.insert(changeover.from.clone(), *line) {
+            return Err(format!("line {}: {} is already replaced on line {}", line, changeover.from, previous));
+        }
+
+        changeovers.push(changeover);
+    }
+
+    for ((line, _), changeover) in lines.iter().zip(&changeovers) {
+        let next = changeovers.iter().find(|next| next.from == changeover.to);
+        if let Some(next) = next.filter(|next| next.date <= changeover.date) {
+            return Err(format!("line {}: {} is replaced by {} before {} replaces it", line, changeover.to, next.to, changeover.from));
+        }
+    }
+
+    Ok(changeovers)
+}
+
+fn parse_changeover(text: &str, records: &[CurrencyRecord]) -> Result<ChangeoverRecord, String> {
+    let fields: Vec<&str> = text.split(',').collect();
+    if fields.len() != 4 {
+        return Err(format!("expected 4 fields but found {}", fields.len()));
+    }
+    if fields.iter().any(|field| field.is_empty() || field.trim() != *field) {
+        return Err("fields can't be empty or start or end with spaces".to_string());
+    }
+
+    for code in &fields[..2] {
+        if !records.iter().any(|record| record.code == *code) {
+            return Err(format!("{} is not in the currency table", code));
+        }
+    }
+    if fields[0] == fields[1] {
+        return Err(format!("{} can't be replaced by itself", fields[0]));
+    }
+
+    let date = parse_date(fields[2]).ok_or_else(|| format!("{} is not a date in the YYYY-MM-DD format", fields[2]))?;
+
+    let digits = fields[3].replacen('.', "", 1);
+    let is_decimal = !fields[3].starts_with('.') && !fields[3].ends_with('.') && digits.len() <= 28 && digits.chars().all(|c| c.is_ascii_digit());
+    if !is_decimal || digits.chars().all(|c| c == '0') {
+        return Err(format!("{} is not a positive decimal factor", fields[3]));
+    }
+
+    Ok(ChangeoverRecord {
+        from: fields[0].to_string(),
+        to: fields[1].to_string(),
+        date,
+        factor: fields[3].to_string(),
+    })
+}
+
+fn parse_date(text: &str) -> Option<(i32, u32, u32)> {
+    let parts: Vec<&str> = text.split('-').collect();
+    if parts.len() != 3 || [4, 2, 2].iter().zip(&parts).any(|(len, part)| part.len() != *len || !part.chars().all(|c| c.is_ascii_digit())) {
+        return None;
+    }
+
+    let (year, month, day) = (parts[0].parse::<i32>().ok()?, parts[1].parse::<u32>().ok()?, parts[2].parse::<u32>().ok()?);
+    let is_leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        2 if is_leap_year => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => return None,
+    };
+
+    (1..=days_in_month).contains(&day).then_some((year, month, day))
+}
+
+/// Writes the `CHANGEOVERS` table for `changeovers`.
+pub fn generate_changeovers(changeovers: &[ChangeoverRecord]) -> String {
+    let mut code = String::from("// Generated by build.rs from data/changeovers.csv. Edit that file instead.\n");
+
+    code.push_str(&format!("\nconst CHANGEOVERS: [ChangeoverData; {}] = [\n", changeovers.len()));
+    for changeover in changeovers {
+        let (year, month, day) = changeover.date;
+        code.push_str(&format!(
+            "    ChangeoverData {{ from: Currency::{}, to: Currency::{}, date: ({}, {}, {}), factor: dec!({}) }},\n",
+            changeover.from, changeover.to, year, month, day, changeover.factor,
+        ));
+    }
+    code.push_str("];\n");
+
+    code
+}
@@ -0,0 +1,129 @@
+use crate::{Currency, Money, MoneyError};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// An amount stored as a whole number of its currency's minor units, such as cents, for hot
+/// loops where `Money`'s `Decimal` arithmetic is too slow.
+///
+/// Amounts range over what an `i64` of minor units holds, about ±92 quadrillion dollars for
+/// USD. Arithmetic is checked and returns `MoneyError::Overflow` instead of wrapping, and
+/// converting to and from `Money` never rounds.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FixedMoney {
+    minor_units: i64,
+    currency: Currency,
+}
+
+impl FixedMoney {
+    pub fn new(minor_units: i64, currency: Currency) -> FixedMoney {
+        FixedMoney { minor_units, currency }
+    }
+
+    pub fn zero(currency: Currency) -> FixedMoney {
+        FixedMoney::new(0, currency)
+    }
+
+    pub fn minor_units(&self) -> i64 {
+        self.minor_units
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// Converts `money` into minor units. Amounts with more decimal places than the currency's
+    /// minor unit are rejected rather than rounded; round them first with
+    /// `Money::round_to_minor_unit`.
+    pub fn from_money(money: Money) -> Result<FixedMoney, MoneyError> {
+        let scale = Decimal::from(10_i64.checked_pow(money.currency.match_currency_to_data().exponent.into()).ok_or(MoneyError::Overflow)?);
+        let minor_units = money.amount.checked_mul(scale).ok_or(MoneyError::Overflow)?;
+        if !minor_units.fract().is_zero() {
+            return Err(MoneyError::NotWholeMinorUnits(money));
+        }
+
+        let minor_units = minor_units.to_i64().ok_or(MoneyError::Overflow)?;
+        Ok(FixedMoney::new(minor_units, money.currency))
+    }
+
+    pub fn to_money(&self) -> Money {
+        let exponent = self.currency.match_currency_to_data().exponent;
+        Money { amount: Decimal::new(self.minor_units, exponent.into()), currency: self.currency }
+    }
+
+    pub fn checked_add(self, other: FixedMoney) -> Result<FixedMoney, MoneyError> {
+        if self.currency != other.currency {
+            return Err(MoneyError::MismatchedCurrencies);
+        }
+
+        let minor_units = self.minor_units.checked_add(other.minor_units).ok_or(MoneyError::Overflow)?;
+        Ok(FixedMoney::new(minor_units, self.currency))
+    }
+
+    pub fn checked_sub(self, other: FixedMoney) -> Result<FixedMoney, MoneyError> {
+        if self.currency != other.currency {
+            return Err(MoneyError::MismatchedCurrencies);
+        }
+
+        let minor_units = self.minor_units.checked_sub(other.minor_units).ok_or(MoneyError::Overflow)?;
+        Ok(FixedMoney::new(minor_units, self.currency))
+    }
+
+    pub fn checked_neg(self) -> Result<FixedMoney, MoneyError> {
+        let minor_units = self.minor_units.checked_neg().ok_or(MoneyError::Overflow)?;
+        Ok(FixedMoney::new(minor_units, self.currency))
+    }
+
+    pub fn checked_mul(self, factor: i64) -> Result<FixedMoney, MoneyError> {
+        let minor_units = self.minor_units.checked_mul(factor).ok_or(MoneyError::Overflow)?;
+        Ok(FixedMoney::new(minor_units, self.currency))
+    }
+
+    /// Adds up `amounts`, which must all be in `currency`.
+    pub fn checked_sum<I: IntoIterator<Item = FixedMoney>>(currency: Currency, amounts: I) -> Result<FixedMoney, MoneyError> {
+        let mut minor_units: i64 = 0;
+        for amount in amounts {
+            if amount.currency != currency {
+                return Err(MoneyError::MismatchedCurrencies);
+            }
+            minor_units = minor_units.checked_add(amount.minor_units).ok_or(MoneyError::Overflow)?;
+        }
+
+        Ok(FixedMoney::new(minor_units, currency))
+    }
+}
+
+impl From<FixedMoney> for Money {
+    fn from(money: FixedMoney) -> Money {
+        money.to_money()
+    }
+}
+
+impl TryFrom<Money> for FixedMoney {
+    type Error = MoneyError;
+
+    fn try_from(money: Money) -> Result<FixedMoney, MoneyError> {
+        FixedMoney::from_money(money)
+    }
+}
+
+impl PartialOrd for FixedMoney {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.currency != other.currency {
+            return None;
+        }
+
+        self.minor_units.partial_cmp(&other.minor_units)
+    }
+}
+
+impl fmt::Display for FixedMoney {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.to_money().fmt(f)
+    }
+}
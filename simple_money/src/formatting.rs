@@ -0,0 +1,305 @@
+use crate::currency::Locale;
+use crate::{Currency, Money, MoneyError, RoundingPolicy};
+use rust_decimal::Decimal;
+use rust_decimal_macros::*;
+
+/// How the digits before the decimal separator are grouped.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum DigitGrouping {
+    /// Groups of three: 1,000,000.
+    Thousands,
+    /// The Indian numbering system, with the first group of three and every group after it of
+    /// two: 10,00,000 (ten lakh) and 1,00,00,000 (one crore).
+    Indian,
+    /// No grouping at all: 1000000.
+    None,
+}
+
+/// The digit characters numbers are written with.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Digits {
+    /// 0123456789
+    Latin,
+    /// ٠١٢٣٤٥٦٧٨٩, used across most of the Arabic-speaking Middle East.
+    ArabicIndic,
+    /// ۰۱۲۳۴۵۶۷۸۹, used in Persian and Urdu.
+    ExtendedArabicIndic,
+    /// ०१२३४५६७८९
+    Devanagari,
+    /// ০১২৩৪৫৬৭৮৯
+    Bengali,
+}
+
+impl Digits {
+    const ALL: [Digits; 5] = [Digits::Latin, Digits::ArabicIndic, Digits::ExtendedArabicIndic, Digits::Devanagari, Digits::Bengali];
+
+    /// Maps a digit from any supported script back to its Latin digit.
+    pub(crate) fn to_latin(digit: char) -> Option<char> {
+        Digits::ALL.iter().find_map(|digits| {
+            let offset = (digit as u32).checked_sub(digits.zero() as u32)?;
+            if offset < 10 {
+                char::from_digit(offset, 10)
+            } else {
+                None
+            }
+        })
+    }
+
+    fn zero(&self) -> char {
+        match self {
+            Digits::Latin => '0',
+            Digits::ArabicIndic => '\u{0660}',
+            Digits::ExtendedArabicIndic => '\u{06F0}',
+            Digits::Devanagari => '\u{0966}',
+            Digits::Bengali => '\u{09E6}',
+        }
+    }
+
+    fn map(&self, digit: char) -> char {
+        match digit.to_digit(10) {
+            Some(value) => char::from_u32(self.zero() as u32 + value).unwrap_or(digit),
+            None => digit,
+        }
+    }
+}
+
+/// The separators, grouping and digits used to write a number.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct NumberFormat {
+    pub grouping_separator: &'static str,
+    pub decimal_separator: &'static str,
+    pub grouping: DigitGrouping,
+    pub digits: Digits,
+}
+
+impl NumberFormat {
+    /// Writes `value` rounded half away from zero to `precision` decimal places, with a leading
+    /// `-` if it is negative.
+    pub fn format(&self, value: Decimal, precision: u32) -> String {
+        let rounded = RoundingPolicy::HalfUp.round(value, precision);
+        let unsigned = self.format_unsigned(rounded, precision);
+        if rounded.is_sign_negative() && !rounded.is_zero() {
+            format!("-{}", unsigned)
+        } else {
+            unsigned
+        }
+    }
+
+    /// Writes the magnitude of `value`, leaving it to the caller to show the sign.
+    pub(crate) fn format_unsigned(&self, value: Decimal, precision: u32) -> String {
+        let plain = format!("{:.*}", precision as usize, RoundingPolicy::HalfUp.round(value, precision).abs());
+        let (integer, fraction) = match plain.find('.') {
+            Some(point) => (&plain[..point], Some(&plain[point + 1..])),
+            None => (plain.as_str(), None),
+        };
+
+        let mut formatted = self.group(integer);
+        if let Some(fraction) = fraction {
+            formatted.push_str(self.decimal_separator);
+            formatted.push_str(fraction);
+        }
+
+        formatted.chars().map(|c| self.digits.map(c)).collect()
+    }
+
+    fn group(&self, integer: &str) -> String {
+        let group_sizes: &[usize] = match self.grouping {
+            DigitGrouping::Thousands => &[3],
+            DigitGrouping::Indian => &[3, 2],
+            DigitGrouping::None => return integer.to_string(),
+        };
+
+        let mut groups = vec![];
+        let mut end = integer.len();
+        let mut sizes = group_sizes.iter().chain(std::iter::repeat(group_sizes.last().unwrap()));
+        while end > 0 {
+            let size = *sizes.next().unwrap();
+            let start = end.saturating_sub(size);
+            groups.push(&integer[start..end]);
+            end = start;
+        }
+
+        groups.reverse();
+        groups.join(self.grouping_separator)
+    }
+}
+
+impl Locale {
+    /// How numbers are written in this locale.
+    pub fn number_format(&self) -> NumberFormat {
+        match self {
+            Locale::USA => NumberFormat { grouping_separator: ",", decimal_separator: ".", grouping: DigitGrouping::Thousands, digits: Digits::Latin },
+            Locale::EU => NumberFormat { grouping_separator: ".", decimal_separator: ",", grouping: DigitGrouping::Thousands, digits: Digits::Latin },
+            Locale::India => NumberFormat { grouping_separator: ",", decimal_separator: ".", grouping: DigitGrouping::Indian, digits: Digits::Latin },
+            Locale::Poland => NumberFormat { grouping_separator: "\u{00A0}", decimal_separator: ",", grouping: DigitGrouping::Thousands, digits: Digits::Latin },
+            Locale::Switzerland => NumberFormat { grouping_separator: "\u{2019}", decimal_separator: ".", grouping: DigitGrouping::Thousands, digits: Digits::Latin },
+            Locale::France => NumberFormat { grouping_separator: "\u{202F}", decimal_separator: ",", grouping: DigitGrouping::Thousands, digits: Digits::Latin },
+            Locale::Arabic => NumberFormat { grouping_separator: "\u{066C}", decimal_separator: "\u{066B}", grouping: DigitGrouping::Thousands, digits: Digits::ArabicIndic },
+        }
+    }
+}
+
+/// The most decimal places a `Decimal`, and so a formatted amount, can have.
+const MAX_PRECISION: u32 = 28;
+
+/// The units compact amounts are shown in, from the smallest.
+const COMPACT_UNITS: [(i64, &str); 4] = [(1_000, "K"), (1_000_000, "M"), (1_000_000_000, "B"), (1_000_000_000_000, "T")];
+
+/// How a `MoneyFormat` marks an amount's currency.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum CurrencyDisplay {
+    /// The currency's symbol, on the side the currency usually puts it: $1.00 or 1,00kr.
+    #[default]
+    Symbol,
+    /// The ISO code, separated from the number by a space on the same side: USD 1.00 or
+    /// 1,00 DKK.
+    Code,
+    /// The number alone.
+    None,
+}
+
+/// How a `MoneyFormat` shows negative amounts.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum NegativeStyle {
+    /// Parentheses around the number, as in accounts: $(1.00) or (1,00)kr.
+    #[default]
+    Parentheses,
+    /// A minus sign before everything else: -$1.00 or -1,00kr.
+    MinusSign,
+}
+
+/// Options for writing an amount, built up from `MoneyFormat::new` and passed to
+/// `Money::format`.
+///
+/// The defaults match `Money`'s `Display`: the currency's symbol and usual locale, the
+/// currency's number of decimal places and negatives in parentheses.
+///
+/// ```
+/// use rust_decimal_macros::dec;
+/// use simple_money::{Currency, CurrencyDisplay, Money, MoneyFormat, NegativeStyle};
+///
+/// let loss = Money { amount: dec!(-1_234_567.89), currency: Currency::USD };
+/// let format = MoneyFormat::new().currency_display(CurrencyDisplay::Code).negative_style(NegativeStyle::MinusSign);
+/// assert_eq!(loss.format(&format).unwrap(), "-USD 1,234,567.89");
+/// assert_eq!(loss.format(&MoneyFormat::new().compact(true)).unwrap(), "$(1.2M)");
+/// ```
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct MoneyFormat {
+    currency_display: CurrencyDisplay,
+    negative_style: NegativeStyle,
+    precision: Option<u32>,
+    compact: bool,
+    locale: Option<Locale>,
+}
+
+impl MoneyFormat {
+    pub fn new() -> MoneyFormat {
+        MoneyFormat::default()
+    }
+
+    pub fn currency_display(mut self, currency_display: CurrencyDisplay) -> MoneyFormat {
+        self.currency_display = currency_display;
+        self
+    }
+
+    pub fn negative_style(mut self, negative_style: NegativeStyle) -> MoneyFormat {
+        self.negative_style = negative_style;
+        self
+    }
+
+    /// Rounds amounts half away from zero to `precision` decimal places instead of the
+    /// currency's own. At most 28 places can be shown.
+    pub fn precision(mut self, precision: u32) -> MoneyFormat {
+        self.precision = Some(precision);
+        self
+    }
+
+    /// Shows amounts of a thousand or more in thousands (K), millions (M), billions (B) or
+    /// trillions (T), such as $1.2M or €350K. Compact amounts have 1 decimal place unless a
+    /// precision is set, and drop trailing zeros.
+    pub fn compact(mut self, compact: bool) -> MoneyFormat {
+        self.compact = compact;
+        self
+    }
+
+    /// Writes the number with `locale`'s separators and digits instead of the currency's usual
+    /// locale's.
+    pub fn locale(mut self, locale: Locale) -> MoneyFormat {
+        self.locale = Some(locale);
+        self
+    }
+
+    pub fn format(&self, money: &Money) -> Result<String, MoneyError> {
+        let precision = self.precision_for(money.currency);
+        if precision > MAX_PRECISION {
+            return Err(MoneyError::InvalidFormat(format!("{} decimal places is more than the {} an amount can have", precision, MAX_PRECISION)));
+        }
+
+        Ok(self.write(money, precision))
+    }
+
+    fn precision_for(&self, currency: Currency) -> u32 {
+        match self.precision {
+            Some(precision) => precision,
+            None if self.compact => 1,
+            None => currency.match_currency_to_data().exponent.into(),
+        }
+    }
+
+    /// Writes `money` with `precision` decimal places, which must be no more than
+    /// `MAX_PRECISION`.
+    fn write(&self, money: &Money, precision: u32) -> String {
+        let currency_data = money.currency.match_currency_to_data();
+        let (rounded, unit) = if self.compact { compact_amount(money.amount, precision) } else { (RoundingPolicy::HalfUp.round(money.amount, precision), "") };
+        let number_format = self.locale.unwrap_or(currency_data.locale).number_format();
+        let shown_precision = if self.compact { precision.min(rounded.normalize().scale()) } else { precision };
+        let is_negative = rounded.is_sign_negative() && !rounded.is_zero();
+        let value = format!("{}{}", number_format.format_unsigned(rounded, shown_precision), unit);
+        let value = match self.negative_style {
+            NegativeStyle::Parentheses if is_negative => format!("({})", value),
+            _ => value,
+        };
+
+        let formatted = match (self.currency_display, currency_data.symbol_first) {
+            (CurrencyDisplay::Symbol, true) => format!("{}{}", currency_data.symbol, value),
+            (CurrencyDisplay::Symbol, false) => format!("{}{}", value, currency_data.symbol),
+            (CurrencyDisplay::Code, true) => format!("{} {}", currency_data.code, value),
+            (CurrencyDisplay::Code, false) => format!("{} {}", value, currency_data.code),
+            (CurrencyDisplay::None, _) => value,
+        };
+
+        match self.negative_style {
+            NegativeStyle::MinusSign if is_negative => format!("-{}", formatted),
+            _ => formatted,
+        }
+    }
+}
+
+/// Scales `amount` down to the largest unit it has at least one of once rounded to `precision`
+/// decimal places, and returns the rounded amount in that unit with the unit's suffix.
+fn compact_amount(amount: Decimal, precision: u32) -> (Decimal, &'static str) {
+    let mut compact = (RoundingPolicy::HalfUp.round(amount, precision), "");
+    for (size, suffix) in COMPACT_UNITS.iter() {
+        let scaled = RoundingPolicy::HalfUp.round(amount / Decimal::from(*size), precision);
+        if scaled.abs() < dec!(1) {
+            break;
+        }
+        compact = (scaled, *suffix);
+    }
+
+    compact
+}
+
+impl Money {
+    /// Writes this amount as `format` describes.
+    pub fn format(&self, format: &MoneyFormat) -> Result<String, MoneyError> {
+        format.format(self)
+    }
+
+    /// Formats this amount with its currency's symbol but the number conventions of `locale`,
+    /// for example a EUR amount written the way a US reader expects it: €1,234.56.
+    pub fn format_with_locale(&self, locale: Locale) -> String {
+        // Currencies have at most `MAX_PRECISION` decimal places, so this cannot fail.
+        MoneyFormat::new().locale(locale).write(self, self.currency.match_currency_to_data().exponent.into())
+    }
+}
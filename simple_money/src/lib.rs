@@ -1,15 +1,60 @@
-mod currency;
+pub mod currency;
+mod changeovers;
+mod costs;
+mod fixed_money;
+mod formatting;
+mod money_bag;
+mod parsing;
+mod rate;
+mod rate_files;
+mod rate_matrix;
+mod rate_provider;
+mod registry;
+mod rounding;
+mod snapshot;
+mod triangulation;
+pub mod tvm;
+pub mod typed;
+mod validation;
+#[cfg(feature = "serde")]
+mod serialization;
+#[cfg(feature = "simulation")]
+mod simulation;
+#[cfg(test)]
+mod currency_table;
 
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::ops::{Add, AddAssign, Div, Mul, Sub};
+use std::iter::Sum;
 use std::cmp::Ordering;
-use currency::Currency;
+use std::sync::RwLock;
+pub use currency::{Currency, Locale};
+pub use changeovers::Changeover;
+pub use costs::{Conversion, ConversionCosts, FeeBreakdown};
+pub use fixed_money::FixedMoney;
+pub use formatting::{CurrencyDisplay, DigitGrouping, Digits, MoneyFormat, NegativeStyle, NumberFormat};
+pub use money_bag::{Exposure, MoneyBag};
+pub use parsing::MoneyParseError;
+pub use rate::{Compounding, Rate};
+pub use rate_files::RateFileError;
+use rate_matrix::RateMatrix;
+pub use rate_provider::{CachingProvider, DatedProvider, FallbackProvider, OverrideProvider, RateProvider, ShockProvider};
+pub use registry::CustomCurrency;
+pub use rounding::RoundingPolicy;
+pub use snapshot::ExchangeSnapshot;
+#[cfg(feature = "simulation")]
+pub use simulation::{FxModel, FxPath, FxSimulation};
+pub use triangulation::ConversionPath;
+pub use tvm::PaymentTiming;
+pub use typed::{CurrencyType, TypedMoney};
+pub use validation::{RateIssue, ValidationOptions, ValidationReport};
 use rust_decimal::Decimal;
-use rust_decimal::prelude::ToPrimitive;
+use chrono::NaiveDate;
 use thiserror::Error;
 use rust_decimal_macros::*;
 use std::fmt;
-use ::Lotus::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 #[derive(PartialEq,Debug,Error)]
 pub enum MoneyError{
@@ -17,9 +62,46 @@ pub enum MoneyError{
     CouldNotFindExchangeRate,
     #[error("Mismatched currencies")]
     MismatchedCurrencies,
+    #[error("Could not parse currency from {0}")]
+    CouldNotParseCurrency(String),
+    #[error("Could not allocate money across the given ratios")]
+    InvalidAllocation,
+    #[error("Arithmetic overflow")]
+    Overflow,
+    #[error("{0} is outside the range of loaded exchange rates")]
+    DateOutsideLoadedRates(NaiveDate),
+    #[error("Invalid currency definition: {0}")]
+    InvalidCurrencyDefinition(String),
+    #[error("Currency {0} is already defined")]
+    CurrencyAlreadyDefined(String),
+    #[error("{0} is not a valid rate here")]
+    InvalidRate(Rate),
+    #[error("No solution exists for the given cash flows")]
+    NoSolution,
+    #[error("Could not find a rate within {0} iterations")]
+    DidNotConverge(u32),
+    #[error("{0} is not a whole number of minor units")]
+    NotWholeMinorUnits(Money),
+    #[error("Division by zero")]
+    DivisionByZero,
+    #[error("Cannot add up an empty list of amounts")]
+    EmptySum,
+    #[error("Invalid simulation: {0}")]
+    InvalidSimulation(String),
+    #[error("Invalid exchange rates: {0}")]
+    InvalidRates(ValidationReport),
+    #[error("Invalid format: {0}")]
+    InvalidFormat(String),
+    #[error("Invalid conversion costs: {0}")]
+    InvalidConversionCosts(String),
+    #[error("Conversion fees of {0} are more than the amount converted")]
+    FeesExceedAmount(Money),
+    #[error("The minimum of {0} is more than the maximum of {1}")]
+    InvalidRange(Money, Money),
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Money {
     pub amount: Decimal,
     pub currency: Currency,
@@ -29,35 +111,189 @@ impl Money {
     pub fn is_positive(&self) -> bool {
         self.amount > dec!(0)
     }
+
+    /// Rounds this amount to the currency's minor unit using `policy`.
+    pub fn round_to_minor_unit(&self, policy: RoundingPolicy) -> Money {
+        let exponent = self.currency.match_currency_to_data().exponent;
+        Money { amount: policy.round(self.amount, exponent.into()), currency: self.currency }
+    }
+
+    /// Rounds this amount to the nearest amount that can be paid in cash, with halfway amounts
+    /// rounding away from zero. For CHF, 1.025 becomes 1.05 and 1.02 becomes 1.00. Currencies
+    /// without a cash increment larger than the minor unit are rounded to the minor unit. Fails
+    /// with `MoneyError::Overflow` if the rounded amount is too large for `Decimal`.
+    pub fn round_for_cash(&self) -> Result<Money, MoneyError> {
+        let increment = self.currency.cash_increment();
+        let remainder = self.amount.checked_rem(increment).ok_or(MoneyError::Overflow)?;
+        let mut amount = self.amount - remainder;
+        if remainder.abs() * dec!(2) >= increment {
+            let step = if remainder.is_sign_negative() { -increment } else { increment };
+            amount = amount.checked_add(step).ok_or(MoneyError::Overflow)?;
+        }
+
+        Ok(Money { amount, currency: self.currency })
+    }
+
+    /// Adds up `amounts`, which must all be in `currency`. Unlike summing into a `Result`, an
+    /// empty list totals zero.
+    pub fn checked_sum<I: IntoIterator<Item = Money>>(currency: Currency, amounts: I) -> Result<Money, MoneyError> {
+        amounts.into_iter().try_fold(init_zero_amount(currency), |total, amount| total.checked_add(amount))
+    }
+
+    /// The number of decimal places parts of this amount can be rounded to. That is the
+    /// currency's exponent, unless the amount is too large for `Decimal` to hold that many.
+    fn allocation_exponent(&self) -> u32 {
+        let mut whole_digits = 0;
+        let mut whole = self.amount.trunc().abs();
+        while !whole.is_zero() {
+            whole = (whole / dec!(10)).trunc();
+            whole_digits += 1;
+        }
+        let exponent: u32 = self.currency.match_currency_to_data().exponent.into();
+        // Decimal can always hold 28 significant digits.
+        exponent.min(28_u32.saturating_sub(whole_digits))
+    }
+
+    /// Splits this amount across `ratios`, rounding every part to the currency's minor unit.
+    ///
+    /// The parts always sum exactly to the original amount. Each part is first truncated to
+    /// the minor unit, then the leftover minor units are handed out one at a time to the parts
+    /// that lost the most to truncation, with ties going to the earlier part. Amounts too large
+    /// to keep every minor unit are split at the finest precision `Decimal` can hold for them.
+    pub fn allocate(&self, ratios: &[Decimal]) -> Result<Vec<Money>, MoneyError> {
+        if ratios.is_empty() || ratios.iter().any(|ratio| ratio.is_sign_negative()) {
+            return Err(MoneyError::InvalidAllocation);
+        }
+        let total_ratio = ratios.iter().try_fold(dec!(0), |total, ratio| total.checked_add(*ratio)).ok_or(MoneyError::Overflow)?;
+        if total_ratio.is_zero() {
+            return Err(MoneyError::InvalidAllocation);
+        }
+
+        let exponent = self.allocation_exponent();
+        let exact_shares = ratios
+            .iter()
+            .map(|ratio| self.amount.checked_mul(*ratio).and_then(|share| share.checked_div(total_ratio)).ok_or(MoneyError::Overflow))
+            .collect::<Result<Vec<Decimal>, MoneyError>>()?;
+        let mut parts: Vec<Decimal> = exact_shares.iter().map(|share| RoundingPolicy::Truncate.round(*share, exponent)).collect();
+
+        let mut order: Vec<usize> = (0..parts.len()).collect();
+        order.sort_by(|first, second| {
+            let first_remainder = (exact_shares[*first] - parts[*first]).abs();
+            let second_remainder = (exact_shares[*second] - parts[*second]).abs();
+            second_remainder.cmp(&first_remainder)
+        });
+
+        let allocated = parts.iter().try_fold(dec!(0), |total, part| total.checked_add(*part)).ok_or(MoneyError::Overflow)?;
+        let leftover = self.amount.checked_sub(allocated).ok_or(MoneyError::Overflow)?;
+        let minor_unit = Decimal::new(1, exponent);
+        let leftover_units = leftover.checked_div(minor_unit).ok_or(MoneyError::Overflow)?.trunc();
+
+        // Every part gets an equal number of the leftover minor units, and the parts that lost the
+        // most get one more each until none are left.
+        let part_count = Decimal::from(parts.len());
+        let units_per_part = (leftover_units / part_count).trunc();
+        let extra_units = (leftover_units % part_count).abs();
+        for (position, index) in order.iter().enumerate() {
+            let mut units = units_per_part;
+            if Decimal::from(position) < extra_units {
+                units += if leftover_units.is_sign_negative() { dec!(-1) } else { dec!(1) };
+            }
+            parts[*index] = parts[*index].checked_add(units * minor_unit).ok_or(MoneyError::Overflow)?;
+        }
+
+        // Anything left is smaller than the minor unit, which only happens when the original
+        // amount was itself more precise than the currency allows.
+        parts[order[0]] = parts[order[0]].checked_add(leftover - leftover_units * minor_unit).ok_or(MoneyError::Overflow)?;
+
+        Ok(parts.into_iter().map(|amount| Money { amount, currency: self.currency }).collect())
+    }
+
+    /// Splits this amount into `n` parts that differ by at most one minor unit and sum exactly
+    /// to the original amount.
+    pub fn split(&self, n: usize) -> Result<Vec<Money>, MoneyError> {
+        self.allocate(&vec![dec!(1); n])
+    }
 }
 
 impl fmt::Display for Money {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let formatted = self.currency.match_currency_to_lotus().unwrap().format(self.amount.to_f64().unwrap());
+        let formatted = self.format(&MoneyFormat::new()).map_err(|_| fmt::Error)?;
         write!(f, "{}", formatted)
     }
 }
 
-#[derive(PartialEq, Eq, Hash)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 struct ExchangeRateQuery{
     from: Currency,
     to: Currency,
 }
 
+/// How `Exchange::get_rate_on` picks a rate for a date from a pair's rate history.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RateLookupPolicy {
+    /// Only a rate set on exactly that date is used.
+    ExactDate,
+    /// The most recent rate set on or before that date is used.
+    #[default]
+    LastKnown,
+    /// The rate is linearly interpolated between the rates set on either side of that date.
+    Interpolate,
+}
+
 pub struct Exchange {
-    rates: HashMap<ExchangeRateQuery, Decimal>,
+    rates: RateMatrix<Decimal>,
+    dated_rates: RateMatrix<BTreeMap<NaiveDate, Decimal>>,
+    rounding_policy: RoundingPolicy,
+    rate_lookup_policy: RateLookupPolicy,
+    costs: RateMatrix<ConversionCosts>,
+    pivot_currency: Option<Currency>,
+    follow_inverse_rates: bool,
+    derived_rates: RwLock<RateMatrix<ConversionPath>>,
 }
 
 impl Exchange {
     pub fn new() -> Exchange {
         return Exchange{
-            rates: HashMap::new(),
+            rates: RateMatrix::new(),
+            dated_rates: RateMatrix::new(),
+            rounding_policy: RoundingPolicy::default(),
+            rate_lookup_policy: RateLookupPolicy::default(),
+            costs: RateMatrix::new(),
+            pivot_currency: None,
+            follow_inverse_rates: true,
+            derived_rates: RwLock::new(RateMatrix::new()),
         } 
     }
 
+    pub fn rounding_policy(&self) -> RoundingPolicy {
+        self.rounding_policy
+    }
+
+    /// Sets how converted amounts, and the results of `add`, `sub` and `clamp`, are rounded.
+    /// Defaults to `RoundingPolicy::HalfEven`.
+    pub fn set_rounding_policy(&mut self, rounding_policy: RoundingPolicy) {
+        self.rounding_policy = rounding_policy;
+    }
+
+    pub fn rate_lookup_policy(&self) -> RateLookupPolicy {
+        self.rate_lookup_policy
+    }
+
+    /// Sets how `get_rate_on` and `convert_on` pick a rate for dates without an exact entry.
+    /// Defaults to `RateLookupPolicy::LastKnown`.
+    pub fn set_rate_lookup_policy(&mut self, rate_lookup_policy: RateLookupPolicy) {
+        self.rate_lookup_policy = rate_lookup_policy;
+    }
+
+    fn round(&self, money: Money) -> Money {
+        money.round_to_minor_unit(self.rounding_policy)
+    }
+
     pub fn set_rate(&mut self, from: Currency, to: Currency, rate: Decimal){
-        let key = ExchangeRateQuery { from, to };
-        self.rates.insert(key, rate);
+        self.rates.insert(from, to, rate);
+        self.clear_derived_rates();
     }
 
     pub fn set_rate_and_inverse(&mut self, from: Currency, to: Currency, rate: Decimal) {
@@ -65,106 +301,113 @@ impl Exchange {
         self.set_rate(to, from, Decimal::new(1, 0) / rate);
     }
 
+    /// Returns the rate from `from` to `to`, triangulating through other currencies when the
+    /// pair was not set directly. A rate set only for the other direction is inverted unless
+    /// `set_follow_inverse_rates` is turned off. See `conversion_path` for how the route is
+    /// chosen.
     pub fn get_rate(&self, from: Currency, to: Currency) -> Result<Decimal, MoneyError>{
-        let key = ExchangeRateQuery { from, to };
-        let rate = self.rates.get(&key);
-        if let Some(rate) = rate {
-            Ok(*rate)
-        }else{
-            Err(MoneyError::CouldNotFindExchangeRate)
+        if from == to {
+            return Ok(dec!(1));
+        }
+        if let Some(rate) = self.rates.get(from, to) {
+            return Ok(*rate);
         }
+        if let Some(path) = self.derived_rates.read().unwrap().get(from, to) {
+            return Ok(path.rate);
+        }
+
+        Ok(self.conversion_path(from, to)?.rate)
     }
 
-    pub fn convert(&self, money: Money, currency: Currency) -> Result<Money, MoneyError> {
-        if money.currency == currency {
-            return Ok(money);
-        }
+    pub fn set_rate_on(&mut self, date: NaiveDate, from: Currency, to: Currency, rate: Decimal) {
+        self.dated_rates.get_or_insert_with(from, to, BTreeMap::new).insert(date, rate);
+    }
 
-        let rate = self.get_rate(money.currency, currency)?;
-        let decimal = Decimal::new((money.amount * rate).to_i64().unwrap(), currency.match_currency_to_data().exponent.into());
-        let converted_money = Money { amount: decimal, currency: currency };
-        return Ok(converted_money);
-    }
-    
-    pub fn add(&self, first: Money, second: Money, output_currency: Currency) -> Result<Money, MoneyError> {
-        if first.currency == output_currency && second.currency == output_currency {
-            Ok(first + second)
-        }else{
-            let first_in_output_currency = self.convert(first, output_currency)?;
-            let second_in_output_currency = self.convert(second, output_currency)?;
-            Ok(first_in_output_currency + second_in_output_currency)
-        }
+    pub fn set_rate_and_inverse_on(&mut self, date: NaiveDate, from: Currency, to: Currency, rate: Decimal) {
+        self.set_rate_on(date, from, to, rate);
+        self.set_rate_on(date, to, from, Decimal::new(1, 0) / rate);
     }
 
-    pub fn sub(&self, first: Money, second: Money, output_currency: Currency) -> Result<Money, MoneyError> {
-        if first.currency == output_currency && second.currency == output_currency {
-            Ok(first - second)
-        }else{
-            let first_in_output_currency = self.convert(first, output_currency)?;
-            let second_in_output_currency =self.convert(second, output_currency)?;
-            Ok(first_in_output_currency - second_in_output_currency)
-        }
+    /// Looks up the rate for `date` according to the exchange's `RateLookupPolicy`.
+    ///
+    /// Pairs without any dated rates fall back to the rate set with `set_rate`. Dates before the
+    /// first or after the last dated rate of a pair are rejected with
+    /// `MoneyError::DateOutsideLoadedRates` whatever the policy.
+    ///
+    /// A currency withdrawn by `date` is replaced with its successor at the official conversion
+    /// factor, so a DEM rate for a date in 2005 is the EUR rate scaled by 1.95583, whatever DEM
+    /// rates were set. See `Currency::successor_on`.
+    pub fn get_rate_on(&self, date: NaiveDate, from: Currency, to: Currency) -> Result<Decimal, MoneyError> {
+        self.get_rate_on_or_else(date, from, to, |from, to| self.get_rate(from, to))
     }
 
-    pub fn lt(&self, first: Money, second: Money) -> Result<bool, MoneyError> {
-        if first.currency == second.currency {
-            Ok(first < second)
-        }else{
-            let second_in_first_currency = self.convert(second, first.currency)?;
-            Ok(first < second_in_first_currency)
+    /// Looks up the rate for `date` like `get_rate_on`, using `undated_rate` for pairs without
+    /// any dated rates.
+    pub(crate) fn get_rate_on_or_else<F>(&self, date: NaiveDate, from: Currency, to: Currency, undated_rate: F) -> Result<Decimal, MoneyError>
+    where
+        F: Fn(Currency, Currency) -> Result<Decimal, MoneyError>,
+    {
+        let (from_successor, from_factor) = from.successor_on(date);
+        let (to_successor, to_factor) = to.successor_on(date);
+        if from_successor != from || to_successor != to {
+            let rate = if from_successor == to_successor { dec!(1) } else { self.get_rate_on_or_else(date, from_successor, to_successor, undated_rate)? };
+            return rate.checked_mul(to_factor).and_then(|rate| rate.checked_div(from_factor)).ok_or(MoneyError::Overflow);
         }
-    }
 
-    pub fn lte(&self, first: Money, second: Money) -> Result<bool, MoneyError> {
-        if first.currency == second.currency {
-            Ok(first <= second)
-        }else{
-            let second_in_first_currency = self.convert(second, first.currency)?;
-            Ok(first <= second_in_first_currency)
+        let history = match self.dated_rates.get(from, to) {
+            Some(history) => history,
+            None => return undated_rate(from, to),
+        };
+
+        let (first_date, last_date) = match (history.keys().next(), history.keys().next_back()) {
+            (Some(first_date), Some(last_date)) => (*first_date, *last_date),
+            _ => return Err(MoneyError::CouldNotFindExchangeRate),
+        };
+        if date < first_date || date > last_date {
+            return Err(MoneyError::DateOutsideLoadedRates(date));
         }
-    }
-    
-    pub fn eq(&self, first: Money, second: Money) -> Result<bool, MoneyError> {
-        if first.currency == second.currency {
-            Ok(first == second)
-        }else{
-            let second_in_first_currency = self.convert(second, first.currency)?;
-            Ok(first == second_in_first_currency)
+
+        if let Some(rate) = history.get(&date) {
+            return Ok(*rate);
         }
-    }
 
-    pub fn gte(&self, first: Money, second: Money) -> Result<bool, MoneyError> {
-        if first.currency == second.currency {
-            Ok(first >= second)
-        }else{
-            let second_in_first_currency = self.convert(second, first.currency)?;
-            Ok(first >= second_in_first_currency)
+        let (previous_date, previous_rate) = history.range(..date).next_back().ok_or(MoneyError::DateOutsideLoadedRates(date))?;
+        match self.rate_lookup_policy {
+            RateLookupPolicy::ExactDate => Err(MoneyError::CouldNotFindExchangeRate),
+            RateLookupPolicy::LastKnown => Ok(*previous_rate),
+            RateLookupPolicy::Interpolate => {
+                let (next_date, next_rate) = history.range(date..).next().ok_or(MoneyError::DateOutsideLoadedRates(date))?;
+                let elapsed_days = Decimal::from((date - *previous_date).num_days());
+                let total_days = Decimal::from((*next_date - *previous_date).num_days());
+                Ok(*previous_rate + (*next_rate - *previous_rate) * elapsed_days / total_days)
+            }
         }
     }
 
-    pub fn gt(&self, first: Money, second: Money) -> Result<bool, MoneyError> {
-        if first.currency == second.currency {
-            Ok(first > second)
-        }else{
-            let second_in_first_currency = self.convert(second, first.currency)?;
-            Ok(first > second_in_first_currency)
-        }
+    fn convert_at_rate(&self, money: Money, currency: Currency, rate: Decimal) -> Result<Money, MoneyError> {
+        let amount = money.amount.checked_mul(rate).ok_or(MoneyError::Overflow)?;
+        let converted_money = Money { amount, currency };
+        Ok(self.round(converted_money))
     }
 
-    pub fn clamp(&self, input: Money, min: Money, max: Money, output_currency: Currency) -> Result<Money, MoneyError>{
-        if input.currency != min.currency || input.currency != max.currency {
-            return Err(MoneyError::MismatchedCurrencies);
+    /// Converts `money`, charging the spread and fees set for the pair with
+    /// `set_conversion_costs`, and reports what was charged.
+    pub fn convert(&self, money: Money, currency: Currency) -> Result<Conversion, MoneyError> {
+        if money.currency == currency {
+            return Ok(Conversion { converted: money, fees: FeeBreakdown::none(currency) });
         }
 
-        if input.currency == output_currency && min.currency == output_currency && max.currency == output_currency {
-            Ok(input.clamp(min, max))
-        }else{
-            let input_in_output_currency = self.convert(input, output_currency)?;
-            let min_in_output_currency = self.convert(min, output_currency)?;
-            let max_in_output_currency = self.convert(max, output_currency)?;
+        let rate = self.get_rate(money.currency, currency)?;
+        self.convert_with_costs(money, currency, rate)
+    }
 
-            Ok(input_in_output_currency.clamp(min_in_output_currency, max_in_output_currency))
+    pub fn convert_on(&self, date: NaiveDate, money: Money, currency: Currency) -> Result<Conversion, MoneyError> {
+        if money.currency == currency {
+            return Ok(Conversion { converted: money, fees: FeeBreakdown::none(currency) });
         }
+
+        let rate = self.get_rate_on(date, money.currency, currency)?;
+        self.convert_with_costs(money, currency, rate)
     }
 }
 
@@ -258,13 +501,28 @@ pub trait CheckedSub {
     fn checked_sub(&self, other: Self) -> Result<Self, MoneyError> where Self: Sized;
 }
 
+pub trait CheckedMul<Rhs> {
+    fn checked_mul(&self, other: Rhs) -> Result<Self, MoneyError> where Self: Sized;
+}
+
+pub trait CheckedDiv<Rhs> {
+    type Output;
+
+    fn checked_div(&self, other: Rhs) -> Result<Self::Output, MoneyError>;
+}
+
+pub trait CheckedNeg {
+    fn checked_neg(&self) -> Result<Self, MoneyError> where Self: Sized;
+}
+
 impl CheckedAdd for Money {
     fn checked_add(&self, other: Self) -> Result<Self, MoneyError> {
         if self.currency != other.currency {
             return Err(MoneyError::MismatchedCurrencies);
         }
 
-        Ok(*self + other)
+        let amount = self.amount.checked_add(other.amount).ok_or(MoneyError::Overflow)?;
+        Ok(Money { amount, currency: self.currency })
     }
 }
 
@@ -274,7 +532,78 @@ impl CheckedSub for Money {
             return Err(MoneyError::MismatchedCurrencies)
         }
 
-        Ok(*self - other)
+        let amount = self.amount.checked_sub(other.amount).ok_or(MoneyError::Overflow)?;
+        Ok(Money { amount, currency: self.currency })
+    }
+}
+
+impl CheckedMul<Decimal> for Money {
+    fn checked_mul(&self, other: Decimal) -> Result<Self, MoneyError> {
+        let amount = self.amount.checked_mul(other).ok_or(MoneyError::Overflow)?;
+        Ok(Money { amount, currency: self.currency })
+    }
+}
+
+impl CheckedMul<i64> for Money {
+    fn checked_mul(&self, other: i64) -> Result<Self, MoneyError> {
+        self.checked_mul(Decimal::from(other))
+    }
+}
+
+impl CheckedMul<i32> for Money {
+    fn checked_mul(&self, other: i32) -> Result<Self, MoneyError> {
+        self.checked_mul(Decimal::from(other))
+    }
+}
+
+impl CheckedDiv<Decimal> for Money {
+    type Output = Money;
+
+    fn checked_div(&self, other: Decimal) -> Result<Money, MoneyError> {
+        if other.is_zero() {
+            return Err(MoneyError::DivisionByZero);
+        }
+
+        let amount = self.amount.checked_div(other).ok_or(MoneyError::Overflow)?;
+        Ok(Money { amount, currency: self.currency })
+    }
+}
+
+/// Unlike `Div<Money>`, the currencies must match.
+impl CheckedDiv<Money> for Money {
+    type Output = Decimal;
+
+    fn checked_div(&self, other: Money) -> Result<Decimal, MoneyError> {
+        if self.currency != other.currency {
+            return Err(MoneyError::MismatchedCurrencies);
+        }
+        if other.amount.is_zero() {
+            return Err(MoneyError::DivisionByZero);
+        }
+
+        self.amount.checked_div(other.amount).ok_or(MoneyError::Overflow)
+    }
+}
+
+impl CheckedNeg for Money {
+    fn checked_neg(&self) -> Result<Self, MoneyError> {
+        // A Decimal's sign is stored apart from its digits, so negating it can't overflow.
+        Ok(Money { amount: -self.amount, currency: self.currency })
+    }
+}
+
+/// Adds up amounts in a single currency, failing on the first amount in another currency or on
+/// overflow. An empty list has no currency to total in, so it is `MoneyError::EmptySum`.
+impl Sum<Money> for Result<Money, MoneyError> {
+    fn sum<I: Iterator<Item = Money>>(mut iter: I) -> Self {
+        let first = iter.next().ok_or(MoneyError::EmptySum)?;
+        iter.try_fold(first, |total, amount| total.checked_add(amount))
+    }
+}
+
+impl<'a> Sum<&'a Money> for Result<Money, MoneyError> {
+    fn sum<I: Iterator<Item = &'a Money>>(iter: I) -> Self {
+        iter.copied().sum()
     }
 }
 
@@ -361,6 +690,145 @@ mod tests {
         assert_eq!(one_hundred_thousand_eur.to_string(), "€100.000,00");
     }
 
+    #[test]
+    fn stringifies_with_each_currency_locale(){
+        let ten_million_inr = Money { amount: dec!(10_000_000.50), currency: Currency::INR };
+        let one_lakh_inr = Money { amount: dec!(100_000), currency: Currency::INR };
+        let chf = Money { amount: dec!(1_234_567.891), currency: Currency::CHF };
+        let pln = Money { amount: dec!(1_234.5), currency: Currency::PLN };
+        let xof = Money { amount: dec!(1_234_567), currency: Currency::XOF };
+        let sar = Money { amount: dec!(1_234.5), currency: Currency::SAR };
+
+        assert_eq!(ten_million_inr.to_string(), "₹1,00,00,000.50");
+        assert_eq!(one_lakh_inr.to_string(), "₹1,00,000.00");
+        assert_eq!(chf.to_string(), "Fr1\u{2019}234\u{2019}567.89");
+        assert_eq!(pln.to_string(), "1\u{a0}234,50zł");
+        assert_eq!(xof.to_string(), "1\u{202f}234\u{202f}567Fr");
+        assert_eq!(sar.to_string(), "ر.س١٬٢٣٤٫٥٠");
+    }
+
+    #[test]
+    fn stringifies_negative_and_zero_amounts(){
+        assert_eq!(init_usd_money(dec!(-1_234.5)).to_string(), "$(1,234.50)");
+        assert_eq!(init_usd_money(dec!(-0.001)).to_string(), "$0.00");
+        assert_eq!(init_zero_amount(Currency::EUR).to_string(), "€0,00");
+        assert_eq!(init_zero_amount(Currency::JPY).to_string(), "¥0");
+    }
+
+    #[test]
+    fn formats_negatives_the_same_way_on_either_side(){
+        let dkk = init_dkk_money(dec!(-1_234.5));
+        let minus = MoneyFormat::new().negative_style(NegativeStyle::MinusSign);
+
+        assert_eq!(dkk.to_string(), "(1.234,50)kr.");
+        assert_eq!(dkk.format_with_locale(Locale::USA), "(1,234.50)kr.");
+        assert_eq!(dkk.format(&minus).unwrap(), "-1.234,50kr.");
+        assert_eq!(init_usd_money(dec!(-1_234.5)).format(&minus).unwrap(), "-$1,234.50");
+        assert_eq!(init_usd_money(dec!(-0.001)).format(&minus).unwrap(), "$0.00");
+        assert_eq!(Money::parse(&dkk.to_string(), Currency::DKK, Locale::EU), Ok(dkk));
+        assert_eq!(Money::parse(&dkk.format(&minus).unwrap(), Currency::DKK, Locale::EU), Ok(dkk));
+    }
+
+    #[test]
+    fn formats_with_codes_and_precision(){
+        let usd = init_usd_money(dec!(1_234.5678));
+        let code = MoneyFormat::new().currency_display(CurrencyDisplay::Code);
+
+        assert_eq!(usd.format(&code).unwrap(), "USD 1,234.57");
+        assert_eq!(init_dkk_money(dec!(-1)).format(&code).unwrap(), "(1,00) DKK");
+        assert_eq!(usd.format(&code.precision(3)).unwrap(), "USD 1,234.568");
+        assert_eq!(usd.format(&MoneyFormat::new().currency_display(CurrencyDisplay::None).precision(0)).unwrap(), "1,235");
+        assert_eq!(usd.format(&MoneyFormat::new().locale(Locale::EU)).unwrap(), "$1.234,57");
+        assert_eq!(usd.format(&MoneyFormat::new()).unwrap(), usd.to_string());
+        assert_eq!(usd.format(&code).unwrap().parse::<Money>(), Ok(init_usd_money(dec!(1_234.57))));
+        assert_eq!(usd.format(&MoneyFormat::new().precision(29)), Err(MoneyError::InvalidFormat("29 decimal places is more than the 28 an amount can have".to_string())));
+    }
+
+    #[test]
+    fn formats_compact_amounts(){
+        let compact = MoneyFormat::new().compact(true);
+
+        assert_eq!(init_usd_money(dec!(1_234_567)).format(&compact).unwrap(), "$1.2M");
+        assert_eq!(init_eur_money(dec!(350_000)).format(&compact).unwrap(), "€350K");
+        assert_eq!(init_eur_money(dec!(1_250_000)).format(&compact).unwrap(), "€1,3M");
+        assert_eq!(init_usd_money(dec!(999_960)).format(&compact).unwrap(), "$1M");
+        assert_eq!(init_usd_money(dec!(999.99)).format(&compact).unwrap(), "$1K");
+        assert_eq!(init_usd_money(dec!(12.34)).format(&compact).unwrap(), "$12.3");
+        assert_eq!(init_usd_money(dec!(-2_500_000_000)).format(&compact).unwrap(), "$(2.5B)");
+        assert_eq!(init_usd_money(dec!(7_000_000_000_000_000)).format(&compact).unwrap(), "$7,000T");
+        assert_eq!(init_usd_money(dec!(1_234_567)).format(&compact.precision(2).currency_display(CurrencyDisplay::Code)).unwrap(), "USD 1.23M");
+        assert_eq!(init_dkk_money(dec!(-45_000)).format(&compact.negative_style(NegativeStyle::MinusSign)).unwrap(), "-45Kkr.");
+    }
+
+    #[test]
+    fn can_format_with_a_different_locale(){
+        let eur = init_eur_money(dec!(1_234_567.89));
+
+        assert_eq!(eur.format_with_locale(Locale::USA), "€1,234,567.89");
+        assert_eq!(eur.format_with_locale(Locale::India), "€12,34,567.89");
+        assert_eq!(eur.format_with_locale(Locale::EU), eur.to_string());
+    }
+
+    #[test]
+    fn number_format_handles_grouping_and_digits(){
+        let hindi = NumberFormat { grouping_separator: ",", decimal_separator: ".", grouping: DigitGrouping::Indian, digits: Digits::Devanagari };
+        let ungrouped = NumberFormat { grouping: DigitGrouping::None, ..Locale::USA.number_format() };
+
+        assert_eq!(hindi.format(dec!(1234567.891), 2), "१२,३४,५६७.८९");
+        assert_eq!(hindi.format(dec!(-999), 0), "-९९९");
+        assert_eq!(ungrouped.format(dec!(1234567.005), 2), "1234567.01");
+        assert_eq!(Locale::USA.number_format().format(dec!(-0.004), 2), "0.00");
+    }
+
+    #[test]
+    fn can_parse_in_a_given_locale(){
+        assert_eq!(Money::parse("€100.000,00", Currency::EUR, Locale::EU), Ok(init_eur_money(dec!(100_000))));
+        assert_eq!(Money::parse("1,00kr.", Currency::DKK, Locale::EU), Ok(init_dkk_money(dec!(1))));
+        assert_eq!(Money::parse("₹1,00,00,000.50", Currency::INR, Locale::India), Ok(Money { amount: dec!(10_000_000.50), currency: Currency::INR }));
+        assert_eq!(Money::parse("1 234,5 zł", Currency::PLN, Locale::Poland), Ok(Money { amount: dec!(1_234.5), currency: Currency::PLN }));
+        assert_eq!(Money::parse("CHF 1'234.50", Currency::CHF, Locale::Switzerland), Ok(Money { amount: dec!(1_234.50), currency: Currency::CHF }));
+        assert_eq!(Money::parse("١٬٢٣٤٫٥٠", Currency::SAR, Locale::Arabic), Ok(Money { amount: dec!(1_234.50), currency: Currency::SAR }));
+        assert_eq!(Money::parse("1234.5", Currency::USD, Locale::USA), Ok(init_usd_money(dec!(1_234.5))));
+    }
+
+    #[test]
+    fn can_parse_negative_amounts(){
+        let expected = Ok(init_usd_money(dec!(-1_234.50)));
+
+        assert_eq!(Money::parse("$(1,234.50)", Currency::USD, Locale::USA), expected);
+        assert_eq!(Money::parse("($1,234.50)", Currency::USD, Locale::USA), expected);
+        assert_eq!(Money::parse("-$1,234.50", Currency::USD, Locale::USA), expected);
+        assert_eq!(Money::parse("$-1,234.50", Currency::USD, Locale::USA), expected);
+        assert_eq!(Money::parse("1,234.50- USD", Currency::USD, Locale::USA), expected);
+        assert_eq!(Money::parse("-(1,234.50)", Currency::USD, Locale::USA), Err(MoneyParseError::InvalidNumber("(1,234.50)".to_string())));
+    }
+
+    #[test]
+    fn parse_rejects_malformed_amounts(){
+        assert_eq!(Money::parse("  ", Currency::USD, Locale::USA), Err(MoneyParseError::Empty));
+        assert_eq!(Money::parse("1.234,56", Currency::USD, Locale::USA), Err(MoneyParseError::InvalidNumber("1.234,56".to_string())));
+        assert_eq!(Money::parse("1,23,456.00", Currency::USD, Locale::USA), Err(MoneyParseError::InvalidNumber("1,23,456.00".to_string())));
+        assert_eq!(Money::parse("12 apples", Currency::USD, Locale::USA), Err(MoneyParseError::InvalidNumber("12 apples".to_string())));
+        assert_eq!(
+            Money::parse("EUR 12.00", Currency::USD, Locale::USA),
+            Err(MoneyParseError::MismatchedCurrency { expected: Currency::USD, found: Currency::EUR })
+        );
+    }
+
+    #[test]
+    fn from_str_detects_the_currency(){
+        assert_eq!("USD 1,234.56".parse::<Money>(), Ok(init_usd_money(dec!(1_234.56))));
+        assert_eq!("1.234,56 eur".parse::<Money>(), Ok(init_eur_money(dec!(1_234.56))));
+        assert_eq!("€100.000,00".parse::<Money>(), Ok(init_eur_money(dec!(100_000))));
+        assert_eq!("(1 234,50zł)".parse::<Money>(), Ok(Money { amount: dec!(-1_234.50), currency: Currency::PLN }));
+        assert_eq!(init_eur_money(dec!(-42.5)).to_string().parse::<Money>(), Ok(init_eur_money(dec!(-42.5))));
+        assert_eq!("1,234.56".parse::<Money>(), Err(MoneyParseError::MissingCurrency("1,234.56".to_string())));
+        assert!(matches!(
+            "$1.00".parse::<Money>(),
+            Err(MoneyParseError::AmbiguousSymbol { symbol, candidates }) if symbol == "$" && candidates.contains(&Currency::USD) && candidates.contains(&Currency::CAD)
+        ));
+    }
+
     #[test]
     fn can_determine_positivity(){
         let one_cad = init_cad_money(dec!(1.00));
@@ -507,6 +975,18 @@ mod tests {
         assert_rounded_eq!(clamped_usd, expected_clamped_usd);
     }
 
+    #[test]
+    fn clamp_rejects_a_minimum_above_the_maximum(){
+        let exchange = setup();
+
+        let input = init_cad_money(dec!(2));
+        let min = init_usd_money(dec!(3));
+        let max = init_cad_money(dec!(3));
+
+        assert_eq!(exchange.clamp(input, min, max, Currency::CAD).unwrap_err(), MoneyError::InvalidRange(init_cad_money(dec!(3.90)), init_cad_money(dec!(3))));
+        assert_eq!(exchange.clamp(input, max, min, Currency::CAD).unwrap(), init_cad_money(dec!(3)));
+    }
+
     #[test]
     fn can_add_different_currencies_and_get_converted_result(){
         let exchange = setup();
@@ -591,4 +1071,1370 @@ mod tests {
         assert_eq!(diff_without_exchange_checked, expected_cad_diff);
         assert_eq!(diff_without_exchange_unchecked, expected_cad_diff);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn can_parse_currency_from_alphabetic_and_numeric_codes(){
+        assert_eq!("CAD".parse::<Currency>().unwrap(), Currency::CAD);
+        assert_eq!(" eur ".parse::<Currency>().unwrap(), Currency::EUR);
+        assert_eq!("978".parse::<Currency>().unwrap(), Currency::EUR);
+        assert_eq!("008".parse::<Currency>().unwrap(), Currency::ALL);
+        assert_eq!(Currency::from_numeric_code(124).unwrap(), Currency::CAD);
+
+        assert_eq!("XYZ".parse::<Currency>().unwrap_err(), MoneyError::CouldNotParseCurrency("XYZ".to_string()));
+        assert_eq!("999".parse::<Currency>().unwrap_err(), MoneyError::CouldNotParseCurrency("999".to_string()));
+        assert_eq!("".parse::<Currency>().unwrap_err(), MoneyError::CouldNotParseCurrency("".to_string()));
+    }
+
+    #[test]
+    fn can_print_currency_codes(){
+        assert_eq!(Currency::CAD.to_string(), "CAD");
+        assert_eq!(Currency::CAD.code(), "CAD");
+        assert_eq!(Currency::CAD.numeric_code(), 124);
+        assert_eq!(Currency::DKK.numeric_code(), 208);
+    }
+
+    #[test]
+    fn all_currencies_round_trip_through_their_codes(){
+        assert_eq!(Currency::all().len(), 197);
+
+        for currency in Currency::all() {
+            assert_eq!(currency.code().parse::<Currency>().unwrap(), *currency);
+            assert_eq!(Currency::from_numeric_code(currency.numeric_code()).unwrap(), *currency);
+            assert_eq!(currency.to_string(), format!("{:?}", currency));
+        }
+    }
+
+    #[test]
+    fn rounds_to_the_cash_increment(){
+        let chf = |amount| Money { amount, currency: Currency::CHF };
+
+        assert_eq!(Currency::CHF.cash_increment(), dec!(0.05));
+        assert_eq!(Currency::USD.cash_increment(), dec!(0.01));
+        assert_eq!(Currency::JPY.cash_increment(), dec!(1));
+
+        assert_eq!(chf(dec!(1.02)).round_for_cash().unwrap(), chf(dec!(1.00)));
+        assert_eq!(chf(dec!(1.025)).round_for_cash().unwrap(), chf(dec!(1.05)));
+        assert_eq!(chf(dec!(1.03)).round_for_cash().unwrap(), chf(dec!(1.05)));
+        assert_eq!(chf(dec!(-1.03)).round_for_cash().unwrap(), chf(dec!(-1.05)));
+
+        assert_eq!(init_cad_money(dec!(1.02)).round_for_cash().unwrap(), init_cad_money(dec!(1.00)));
+        assert_eq!(init_cad_money(dec!(1.03)).round_for_cash().unwrap(), init_cad_money(dec!(1.05)));
+        assert_eq!(init_cad_money(dec!(1.08)).round_for_cash().unwrap(), init_cad_money(dec!(1.10)));
+        assert_eq!(init_dkk_money(dec!(10.24)).round_for_cash().unwrap(), init_dkk_money(dec!(10.00)));
+        assert_eq!(init_dkk_money(dec!(10.25)).round_for_cash().unwrap(), init_dkk_money(dec!(10.50)));
+        assert_eq!(init_usd_money(dec!(1.034)).round_for_cash().unwrap(), init_usd_money(dec!(1.03)));
+
+        assert_eq!(chf(Decimal::MAX).round_for_cash().unwrap(), chf(Decimal::MAX));
+        assert_eq!(chf(Decimal::MIN).round_for_cash().unwrap(), chf(Decimal::MIN));
+        assert_eq!(chf(dec!(792281625142643375935439503.34)).round_for_cash().unwrap(), chf(dec!(792281625142643375935439503.35)));
+    }
+
+    #[test]
+    fn can_register_custom_currencies(){
+        let btc = Currency::register("regbtc", 8, "₿", true, Locale::USA).unwrap();
+        let points = Currency::register("REGPTS", 0, " pts", false, Locale::USA).unwrap();
+
+        assert!(btc.is_custom());
+        assert_eq!(btc.code(), "REGBTC");
+        assert_eq!(btc.to_string(), "REGBTC");
+        assert_eq!("REGBTC".parse::<Currency>(), Ok(btc));
+        assert_eq!(Currency::register("REGBTC", 8, "₿", true, Locale::USA), Ok(btc));
+        assert!(Currency::custom().contains(&btc) && Currency::custom().contains(&points));
+        assert!(!Currency::all().contains(&btc));
+
+        assert_eq!(Money { amount: dec!(1234.5), currency: btc }.to_string(), "₿1,234.50000000");
+        assert_eq!(Money { amount: dec!(1500000), currency: points }.format_with_locale(Locale::EU), "1.500.000 pts");
+        assert_eq!("₿0.5".parse::<Money>(), Ok(Money { amount: dec!(0.5), currency: btc }));
+    }
+
+    #[test]
+    fn rejects_invalid_custom_currencies(){
+        assert_eq!(Currency::register("USD", 2, "$", true, Locale::USA), Err(MoneyError::CurrencyAlreadyDefined("USD".to_string())));
+        assert!(matches!(Currency::register("", 2, "?", true, Locale::USA), Err(MoneyError::InvalidCurrencyDefinition(_))));
+        assert!(matches!(Currency::register("X-1", 2, "?", true, Locale::USA), Err(MoneyError::InvalidCurrencyDefinition(_))));
+        assert!(matches!(Currency::register("123", 2, "?", true, Locale::USA), Err(MoneyError::InvalidCurrencyDefinition(_))));
+        assert!(matches!(Currency::register("BIG", 29, "?", true, Locale::USA), Err(MoneyError::InvalidCurrencyDefinition(_))));
+
+        Currency::register("REJOZ", 4, "oz", false, Locale::USA).unwrap();
+        assert_eq!(Currency::register("REJOZ", 2, "oz", false, Locale::USA), Err(MoneyError::CurrencyAlreadyDefined("REJOZ".to_string())));
+    }
+
+    #[test]
+    fn custom_currencies_work_with_exchange(){
+        let eth = Currency::register("EXETH", 18, "Ξ", true, Locale::USA).unwrap();
+        let mut exchange = setup();
+        exchange.set_rate_and_inverse(eth, Currency::USD, dec!(3000));
+
+        let usd = exchange.convert_at_mid_rate(Money { amount: dec!(0.5), currency: eth }, Currency::USD).unwrap();
+        assert_eq!(usd, init_usd_money(dec!(1500)));
+
+        let eth_amount = exchange.convert_at_mid_rate(init_cad_money(dec!(1)), eth).unwrap();
+        assert_eq!(eth_amount.amount.scale(), 18);
+        assert_eq!(eth_amount.amount, dec!(0.000256410256410256));
+
+        let parts = Money { amount: dec!(1), currency: eth }.split(3).unwrap();
+        assert_eq!(parts[0].amount, dec!(0.333333333333333334));
+        assert_eq!(parts.iter().map(|part| part.amount).sum::<Decimal>(), dec!(1));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn custom_currencies_round_trip_through_json(){
+        let sol = Currency::register("JSONSOL", 9, "◎", true, Locale::USA).unwrap();
+        let money = Money { amount: dec!(2.5), currency: sol };
+
+        let json = serde_json::to_string(&money).unwrap();
+        assert_eq!(json, r#"{"amount":"2.5","currency":"JSONSOL"}"#);
+        assert_eq!(serde_json::from_str::<Money>(&json).unwrap(), money);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn can_round_trip_money_through_json(){
+        let money = init_cad_money(dec!(1234567.8912345678901234));
+
+        let json = serde_json::to_string(&money).unwrap();
+        assert_eq!(json, r#"{"amount":"1234567.8912345678901234","currency":"CAD"}"#);
+
+        let deserialized: Money = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, money);
+        assert_eq!(deserialized.amount.to_string(), "1234567.8912345678901234");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn rejects_unknown_currency_codes_in_json(){
+        let result: Result<Money, _> = serde_json::from_str(r#"{"amount":"1.00","currency":"XYZ"}"#);
+        assert!(result.is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn can_round_trip_exchange_through_json(){
+        let mut exchange = Exchange::new();
+        exchange.set_rate_and_inverse(Currency::USD, Currency::CAD, dec!(1.2745));
+        exchange.set_rate_on(NaiveDate::from_ymd_opt(2020, 1, 1).unwrap(), Currency::EUR, Currency::CAD, dec!(1.45));
+        exchange.set_rate_lookup_policy(RateLookupPolicy::ExactDate);
+
+        let json = serde_json::to_string(&exchange).unwrap();
+        let deserialized: Exchange = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(deserialized.get_rate(Currency::USD, Currency::CAD).unwrap(), dec!(1.2745));
+        assert_eq!(deserialized.get_rate_on(NaiveDate::from_ymd_opt(2020, 1, 1).unwrap(), Currency::EUR, Currency::CAD).unwrap(), dec!(1.45));
+        assert_eq!(deserialized.rate_lookup_policy(), RateLookupPolicy::ExactDate);
+        assert_eq!(
+            deserialized.get_rate(Currency::CAD, Currency::USD).unwrap(),
+            exchange.get_rate(Currency::CAD, Currency::USD).unwrap()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn rejects_invalid_conversion_costs_in_json(){
+        let costs = |costs: &str| serde_json::from_str::<Exchange>(&format!(r#"{{"rates":[],"costs":[{{"from":"USD","to":"CAD",{}}}]}}"#, costs));
+
+        let exchange = costs(r#""spread":"0.01","percentage_fee":"0.005","fixed_fee":{"amount":"2.00","currency":"USD"}"#).unwrap();
+        assert_eq!(exchange.conversion_costs(Currency::USD, Currency::CAD).fixed_fee, Some(init_usd_money(dec!(2.00))));
+        assert!(costs(r#""spread":"-0.01","percentage_fee":"0","fixed_fee":null"#).err().unwrap().to_string().contains("Invalid conversion costs"));
+        assert!(costs(r#""spread":"0","percentage_fee":"-0.005","fixed_fee":null"#).is_err());
+        assert!(costs(r#""spread":"0","percentage_fee":"0","fixed_fee":{"amount":"-2.00","currency":"USD"}"#).is_err());
+        assert!(costs(r#""spread":"0","percentage_fee":"0","fixed_fee":{"amount":"2.00","currency":"EUR"}"#).is_err());
+    }
+
+    #[test]
+    fn can_allocate_across_ratios(){
+        let rent = init_cad_money(dec!(100.00));
+
+        let parts = rent.allocate(&[dec!(1), dec!(1), dec!(1)]).unwrap();
+        assert_eq!(parts, vec![
+            init_cad_money(dec!(33.34)),
+            init_cad_money(dec!(33.33)),
+            init_cad_money(dec!(33.33)),
+        ]);
+
+        let parts = rent.allocate(&[dec!(0.7), dec!(0.2), dec!(0.1)]).unwrap();
+        assert_eq!(parts, vec![
+            init_cad_money(dec!(70)),
+            init_cad_money(dec!(20)),
+            init_cad_money(dec!(10)),
+        ]);
+
+        let parts = init_cad_money(dec!(0.05)).allocate(&[dec!(3), dec!(7)]).unwrap();
+        assert_eq!(parts, vec![init_cad_money(dec!(0.02)), init_cad_money(dec!(0.03))]);
+    }
+
+    #[test]
+    fn allocation_always_sums_to_the_original_amount(){
+        let amounts = vec![dec!(100.00), dec!(0.01), dec!(-10.00), dec!(1234.57), dec!(10.005)];
+        let ratios = vec![dec!(1), dec!(2), dec!(3), dec!(0), dec!(0.5)];
+
+        for amount in amounts {
+            let money = init_usd_money(amount);
+            let parts = money.allocate(&ratios).unwrap();
+            let sum = parts.iter().fold(init_zero_amount(Currency::USD), |acc, part| acc + *part);
+            assert_eq!(sum, money);
+        }
+    }
+
+    #[test]
+    fn can_split_negative_amounts_and_zero_exponent_currencies(){
+        let parts = init_usd_money(dec!(-10.00)).split(3).unwrap();
+        assert_eq!(parts, vec![
+            init_usd_money(dec!(-3.34)),
+            init_usd_money(dec!(-3.33)),
+            init_usd_money(dec!(-3.33)),
+        ]);
+
+        let yen = Money { amount: dec!(100), currency: Currency::JPY };
+        let parts = yen.split(3).unwrap();
+        assert_eq!(parts, vec![
+            Money { amount: dec!(34), currency: Currency::JPY },
+            Money { amount: dec!(33), currency: Currency::JPY },
+            Money { amount: dec!(33), currency: Currency::JPY },
+        ]);
+    }
+
+    #[test]
+    fn rejects_invalid_allocations(){
+        let money = init_cad_money(dec!(10));
+
+        assert_eq!(money.split(0).unwrap_err(), MoneyError::InvalidAllocation);
+        assert_eq!(money.allocate(&[dec!(0), dec!(0)]).unwrap_err(), MoneyError::InvalidAllocation);
+        assert_eq!(money.allocate(&[dec!(1), dec!(-1)]).unwrap_err(), MoneyError::InvalidAllocation);
+    }
+
+    #[test]
+    fn can_allocate_amounts_near_the_decimal_limits(){
+        let max = init_usd_money(Decimal::MAX);
+        let parts = max.split(3).unwrap();
+        assert_eq!(Money::checked_sum(Currency::USD, parts).unwrap(), max);
+
+        let wei = Currency::register("ALLOCWEI", 18, "Ξ", true, Locale::USA).unwrap();
+        let money = Money { amount: dec!(1_000_000_000_000), currency: wei };
+        let parts = money.split(3).unwrap();
+        assert_eq!(parts[0].amount, dec!(333333333333.333333333333334));
+        assert_eq!(parts[2].amount, dec!(333333333333.333333333333333));
+        assert_eq!(Money::checked_sum(wei, parts).unwrap(), money);
+
+        assert_eq!(max.allocate(&[dec!(3), dec!(1)]).unwrap_err(), MoneyError::Overflow);
+        assert_eq!(max.allocate(&[Decimal::MAX, Decimal::MAX]).unwrap_err(), MoneyError::Overflow);
+    }
+
+    #[test]
+    fn can_round_to_minor_unit_with_each_policy(){
+        let half = init_cad_money(dec!(2.345));
+        let negative_half = init_cad_money(dec!(-2.345));
+
+        assert_eq!(half.round_to_minor_unit(RoundingPolicy::HalfEven).amount, dec!(2.34));
+        assert_eq!(half.round_to_minor_unit(RoundingPolicy::HalfUp).amount, dec!(2.35));
+        assert_eq!(half.round_to_minor_unit(RoundingPolicy::Floor).amount, dec!(2.34));
+        assert_eq!(half.round_to_minor_unit(RoundingPolicy::Ceil).amount, dec!(2.35));
+        assert_eq!(half.round_to_minor_unit(RoundingPolicy::Truncate).amount, dec!(2.34));
+        assert_eq!(half.round_to_minor_unit(RoundingPolicy::FullPrecision).amount, dec!(2.345));
+
+        assert_eq!(negative_half.round_to_minor_unit(RoundingPolicy::HalfEven).amount, dec!(-2.34));
+        assert_eq!(negative_half.round_to_minor_unit(RoundingPolicy::HalfUp).amount, dec!(-2.35));
+        assert_eq!(negative_half.round_to_minor_unit(RoundingPolicy::Floor).amount, dec!(-2.35));
+        assert_eq!(negative_half.round_to_minor_unit(RoundingPolicy::Ceil).amount, dec!(-2.34));
+        assert_eq!(negative_half.round_to_minor_unit(RoundingPolicy::Truncate).amount, dec!(-2.34));
+
+        let yen = Money { amount: dec!(100.5), currency: Currency::JPY };
+        assert_eq!(yen.round_to_minor_unit(RoundingPolicy::HalfEven).amount, dec!(100));
+    }
+
+    #[test]
+    fn convert_rounds_with_the_exchange_policy(){
+        let mut exchange = setup();
+        let usd = init_usd_money(dec!(1.01));
+
+        assert_eq!(exchange.convert(usd, Currency::CAD).unwrap().converted, init_cad_money(dec!(1.31)));
+
+        exchange.set_rounding_policy(RoundingPolicy::Ceil);
+        assert_eq!(exchange.convert(usd, Currency::CAD).unwrap().converted, init_cad_money(dec!(1.32)));
+
+        exchange.set_rounding_policy(RoundingPolicy::FullPrecision);
+        assert_eq!(exchange.convert(usd, Currency::CAD).unwrap().converted.amount, dec!(1.313));
+
+        let large = init_usd_money(dec!(1_000_000_000.00));
+        exchange.set_rounding_policy(RoundingPolicy::HalfEven);
+        assert_eq!(exchange.convert(large, Currency::CAD).unwrap().converted, init_cad_money(dec!(1_300_000_000.00)));
+    }
+
+    #[test]
+    fn convert_reports_overflow_instead_of_panicking(){
+        let mut exchange = Exchange::new();
+        exchange.set_rate(Currency::USD, Currency::CAD, dec!(1000));
+
+        let huge = init_usd_money(Decimal::MAX);
+        assert_eq!(exchange.convert(huge, Currency::CAD).unwrap_err(), MoneyError::Overflow);
+    }
+
+    fn setup_history() -> Exchange {
+        let mut exchange = Exchange::new();
+        exchange.set_rate_on(NaiveDate::from_ymd_opt(2020, 1, 1).unwrap(), Currency::USD, Currency::CAD, dec!(1.30));
+        exchange.set_rate_on(NaiveDate::from_ymd_opt(2020, 1, 11).unwrap(), Currency::USD, Currency::CAD, dec!(1.40));
+        exchange.set_rate_on(NaiveDate::from_ymd_opt(2020, 2, 1).unwrap(), Currency::USD, Currency::CAD, dec!(1.20));
+        exchange
+    }
+
+    #[test]
+    fn can_look_up_dated_rates_with_each_policy(){
+        let mut exchange = setup_history();
+        let between = NaiveDate::from_ymd_opt(2020, 1, 6).unwrap();
+
+        assert_eq!(exchange.get_rate_on(NaiveDate::from_ymd_opt(2020, 1, 11).unwrap(), Currency::USD, Currency::CAD).unwrap(), dec!(1.40));
+        assert_eq!(exchange.get_rate_on(between, Currency::USD, Currency::CAD).unwrap(), dec!(1.30));
+
+        exchange.set_rate_lookup_policy(RateLookupPolicy::ExactDate);
+        assert_eq!(exchange.get_rate_on(between, Currency::USD, Currency::CAD).unwrap_err(), MoneyError::CouldNotFindExchangeRate);
+
+        exchange.set_rate_lookup_policy(RateLookupPolicy::Interpolate);
+        assert_eq!(exchange.get_rate_on(between, Currency::USD, Currency::CAD).unwrap(), dec!(1.35));
+    }
+
+    #[test]
+    fn dated_lookups_outside_loaded_range_fail(){
+        let exchange = setup_history();
+        let before = NaiveDate::from_ymd_opt(2019, 12, 31).unwrap();
+        let after = NaiveDate::from_ymd_opt(2020, 2, 2).unwrap();
+
+        assert_eq!(exchange.get_rate_on(before, Currency::USD, Currency::CAD).unwrap_err(), MoneyError::DateOutsideLoadedRates(before));
+        assert_eq!(exchange.get_rate_on(after, Currency::USD, Currency::CAD).unwrap_err(), MoneyError::DateOutsideLoadedRates(after));
+        assert_eq!(exchange.get_rate_on(before, Currency::CAD, Currency::USD).unwrap_err(), MoneyError::CouldNotFindExchangeRate);
+    }
+
+    #[test]
+    fn can_convert_on_a_date(){
+        let mut exchange = setup_history();
+        exchange.set_rate(Currency::EUR, Currency::CAD, dec!(1.5));
+        let date = NaiveDate::from_ymd_opt(2020, 1, 20).unwrap();
+
+        assert_eq!(exchange.convert_on(date, init_usd_money(dec!(10)), Currency::CAD).unwrap().converted, init_cad_money(dec!(14)));
+        assert_eq!(exchange.convert_on(date, init_eur_money(dec!(10)), Currency::CAD).unwrap().converted, init_cad_money(dec!(15)));
+        assert_eq!(exchange.convert_on(date, init_cad_money(dec!(10)), Currency::CAD).unwrap().converted, init_cad_money(dec!(10)));
+    }
+
+    #[test]
+    fn inverse_rates_are_followed_unless_disabled(){
+        let mut exchange = Exchange::new();
+        exchange.set_rate(Currency::USD, Currency::CAD, dec!(1.25));
+        assert!(exchange.follow_inverse_rates());
+        assert_eq!(exchange.get_rate(Currency::CAD, Currency::USD).unwrap(), dec!(0.8));
+
+        exchange.set_follow_inverse_rates(false);
+        assert_eq!(exchange.get_rate(Currency::CAD, Currency::USD).unwrap_err(), MoneyError::CouldNotFindExchangeRate);
+        assert_eq!(exchange.snapshot().get_rate(Currency::CAD, Currency::USD).unwrap_err(), MoneyError::CouldNotFindExchangeRate);
+
+        exchange.set_follow_inverse_rates(true);
+        assert_eq!(exchange.get_rate(Currency::CAD, Currency::USD).unwrap(), dec!(0.8));
+        assert_eq!(exchange.snapshot().get_rate(Currency::CAD, Currency::USD).unwrap(), dec!(0.8));
+    }
+
+    #[test]
+    fn can_triangulate_through_intermediate_currencies(){
+        let mut exchange = Exchange::new();
+        exchange.set_rate(Currency::DKK, Currency::EUR, dec!(0.134));
+        exchange.set_rate(Currency::EUR, Currency::USD, dec!(1.2));
+        exchange.set_rate(Currency::USD, Currency::CAD, dec!(1.25));
+
+        let path = exchange.conversion_path(Currency::DKK, Currency::CAD).unwrap();
+        assert_eq!(path.currencies, vec![Currency::DKK, Currency::EUR, Currency::USD, Currency::CAD]);
+        assert_eq!(path.rate, dec!(0.201));
+        assert!(!path.is_direct());
+
+        let converted = exchange.convert(init_dkk_money(dec!(1000)), Currency::CAD).unwrap().converted;
+        assert_eq!(converted, init_cad_money(dec!(201)));
+
+        let inverse_path = exchange.conversion_path(Currency::CAD, Currency::EUR).unwrap();
+        assert_eq!(inverse_path.currencies, vec![Currency::CAD, Currency::USD, Currency::EUR]);
+        assert_eq!(inverse_path.rate.round_dp(6), dec!(0.666667));
+        exchange.set_follow_inverse_rates(false);
+        assert_eq!(exchange.conversion_path(Currency::CAD, Currency::EUR).unwrap_err(), MoneyError::CouldNotFindExchangeRate);
+
+        assert_eq!(exchange.get_rate(Currency::DKK, Currency::JPY).unwrap_err(), MoneyError::CouldNotFindExchangeRate);
+    }
+
+    #[test]
+    fn prefers_direct_rates_and_configured_pivot(){
+        let mut exchange = Exchange::new();
+        exchange.set_rate(Currency::GBP, Currency::EUR, dec!(1.15));
+        exchange.set_rate(Currency::EUR, Currency::CAD, dec!(1.5));
+        exchange.set_rate(Currency::GBP, Currency::USD, dec!(1.4));
+        exchange.set_rate(Currency::USD, Currency::CAD, dec!(1.25));
+
+        let shortest = exchange.conversion_path(Currency::GBP, Currency::CAD).unwrap();
+        assert_eq!(shortest.currencies, vec![Currency::GBP, Currency::EUR, Currency::CAD]);
+
+        exchange.set_pivot_currency(Some(Currency::USD));
+        let through_pivot = exchange.conversion_path(Currency::GBP, Currency::CAD).unwrap();
+        assert_eq!(through_pivot.currencies, vec![Currency::GBP, Currency::USD, Currency::CAD]);
+        assert_eq!(through_pivot.rate, dec!(1.75));
+
+        exchange.set_rate(Currency::GBP, Currency::CAD, dec!(1.7));
+        let direct = exchange.conversion_path(Currency::GBP, Currency::CAD).unwrap();
+        assert!(direct.is_direct());
+        assert_eq!(direct.rate, dec!(1.7));
+    }
+
+    #[test]
+    fn knows_when_currencies_were_replaced(){
+        let changeover = Currency::DEM.changeover().unwrap();
+        assert_eq!(changeover.to, Currency::EUR);
+        assert_eq!(changeover.factor, dec!(1.95583));
+        assert_eq!(Currency::DEM.withdrawn_on(), NaiveDate::from_ymd_opt(1999, 1, 1));
+        assert_eq!(Currency::EUR.introduced_on(), NaiveDate::from_ymd_opt(1999, 1, 1));
+        assert!(Currency::DEM.is_obsolete());
+        assert!(!Currency::EUR.is_obsolete());
+
+        assert!(Currency::DEM.is_valid_on(NaiveDate::from_ymd_opt(1998, 12, 31).unwrap()));
+        assert!(!Currency::DEM.is_valid_on(NaiveDate::from_ymd_opt(1999, 1, 1).unwrap()));
+        assert!(!Currency::VES.is_valid_on(NaiveDate::from_ymd_opt(2018, 8, 19).unwrap()));
+        assert!(Currency::VES.is_valid_on(NaiveDate::from_ymd_opt(2018, 8, 20).unwrap()));
+
+        let date = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+        assert_eq!(Currency::VEB.successor_on(date), (Currency::VES, dec!(100_000_000)));
+        assert_eq!(Currency::ZWD.successor_on(date), (Currency::ZWL, dec!(10_000_000_000_000_000_000_000_000)));
+        assert_eq!(Currency::VEB.successor_on(NaiveDate::from_ymd_opt(2010, 1, 1).unwrap()), (Currency::VEF, dec!(1000)));
+        assert_eq!(Currency::USD.successor_on(date), (Currency::USD, dec!(1)));
+    }
+
+    #[test]
+    fn applies_changeover_factors_after_the_changeover(){
+        let mut exchange = Exchange::new();
+        let before = NaiveDate::from_ymd_opt(1998, 6, 1).unwrap();
+        let after = NaiveDate::from_ymd_opt(2005, 6, 1).unwrap();
+        exchange.set_rate_on(before, Currency::DEM, Currency::USD, dec!(0.56));
+        exchange.set_rate_on(after, Currency::DEM, Currency::USD, dec!(99));
+        exchange.set_rate_on(after, Currency::EUR, Currency::USD, dec!(1.25));
+
+        assert_eq!(exchange.get_rate_on(before, Currency::DEM, Currency::USD).unwrap(), dec!(0.56));
+        assert_eq!(exchange.get_rate_on(after, Currency::DEM, Currency::USD).unwrap(), dec!(1.25) / dec!(1.95583));
+        assert_eq!(exchange.get_rate_on(after, Currency::DEM, Currency::EUR).unwrap(), dec!(1) / dec!(1.95583));
+        assert_eq!(exchange.get_rate_on(after, Currency::EUR, Currency::FRF).unwrap(), dec!(6.55957));
+        assert_eq!(exchange.get_rate_on(after, Currency::DEM, Currency::FRF).unwrap(), dec!(6.55957) / dec!(1.95583));
+
+        let converted = exchange.convert_on(after, Money { amount: dec!(100), currency: Currency::DEM }, Currency::FRF).unwrap();
+        assert_eq!(converted.converted, Money { amount: dec!(335.39), currency: Currency::FRF });
+    }
+
+    #[test]
+    fn redenominations_chain_through_intermediate_currencies(){
+        let mut exchange = Exchange::new();
+        let date = NaiveDate::from_ymd_opt(2019, 3, 1).unwrap();
+        exchange.set_rate_on(date, Currency::USD, Currency::VES, dec!(3300));
+
+        let vef = exchange.convert_on(date, init_usd_money(dec!(1)), Currency::VEF).unwrap().converted;
+        assert_eq!(vef, Money { amount: dec!(330_000_000), currency: Currency::VEF });
+
+        let ves = exchange.convert_on(date, Money { amount: dec!(330_000_000_000), currency: Currency::VEB }, Currency::VES).unwrap().converted;
+        assert_eq!(ves, Money { amount: dec!(3300), currency: Currency::VES });
+    }
+
+    const ECB_DAILY_XML: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<gesmes:Envelope xmlns:gesmes=\"http://www.gesmes.org/xml/2002-08-01\" xmlns=\"http://www.ecb.int/vocabulary/2002-08-01/eurofxref\">
+	<gesmes:subject>Reference rates</gesmes:subject>
+	<gesmes:Sender>
+		<gesmes:name>European Central Bank</gesmes:name>
+	</gesmes:Sender>
+	<Cube>
+		<Cube time='2021-01-29'>
+			<Cube currency='USD' rate='1.2136'/>
+			<Cube currency='CAD' rate='1.5533'/>
+		</Cube>
+		<Cube time='2021-01-28'>
+			<Cube currency='USD' rate='1.2108'/>
+			<Cube currency='CAD' rate='1.5494'/>
+		</Cube>
+	</Cube>
+</gesmes:Envelope>";
+
+    #[test]
+    fn can_load_rates_from_csv(){
+        let mut exchange = Exchange::new();
+        let csv = "date,from,to,rate\n2021-01-28,USD,CAD,1.2797\n\n# a comment\n2021-01-29, USD, CAD, 1.2801\n";
+
+        assert_eq!(exchange.load_csv_rates_from_str(csv).unwrap(), 2);
+        assert_eq!(exchange.get_rate_on(NaiveDate::from_ymd_opt(2021, 1, 28).unwrap(), Currency::USD, Currency::CAD).unwrap(), dec!(1.2797));
+        assert_eq!(exchange.get_rate_on(NaiveDate::from_ymd_opt(2021, 1, 29).unwrap(), Currency::USD, Currency::CAD).unwrap(), dec!(1.2801));
+    }
+
+    #[test]
+    fn csv_errors_report_the_line_number(){
+        let mut exchange = Exchange::new();
+
+        let bad_currency = exchange.load_csv_rates_from_str("date,from,to,rate\n2021-01-28,USD,CAD,1.2797\n2021-01-29,USD,XXY,1.2\n").unwrap_err();
+        assert!(matches!(bad_currency, RateFileError::InvalidCurrency { line: 3, ref value } if value == "XXY"));
+
+        let bad_date = exchange.load_csv_rates_from_str("2021-13-01,USD,CAD,1.2").unwrap_err();
+        assert!(matches!(bad_date, RateFileError::InvalidDate { line: 1, .. }));
+
+        let bad_rate = exchange.load_csv_rates_from_str("2021-01-01,USD,CAD,-1").unwrap_err();
+        assert!(matches!(bad_rate, RateFileError::InvalidRate { line: 1, .. }));
+
+        let missing_rate = exchange.load_csv_rates_from_str("\n2021-01-01,USD,CAD").unwrap_err();
+        assert!(matches!(missing_rate, RateFileError::MissingField { line: 2, field: "rate" }));
+
+        assert!(exchange.get_rate_on(NaiveDate::from_ymd_opt(2021, 1, 28).unwrap(), Currency::USD, Currency::CAD).is_err());
+    }
+
+    #[test]
+    fn can_load_rates_from_ecb_xml(){
+        let mut exchange = Exchange::new();
+
+        assert_eq!(exchange.load_ecb_rates_from_str(ECB_DAILY_XML).unwrap(), 4);
+
+        let date = NaiveDate::from_ymd_opt(2021, 1, 29).unwrap();
+        assert_eq!(exchange.get_rate_on(date, Currency::EUR, Currency::USD).unwrap(), dec!(1.2136));
+        assert_eq!(exchange.get_rate_on(date, Currency::CAD, Currency::EUR).unwrap(), dec!(1) / dec!(1.5533));
+        assert_eq!(exchange.get_rate_on(NaiveDate::from_ymd_opt(2021, 1, 28).unwrap(), Currency::EUR, Currency::CAD).unwrap(), dec!(1.5494));
+    }
+
+    #[test]
+    fn ecb_errors_report_the_line_number(){
+        let mut exchange = Exchange::new();
+        let bad_rate = ECB_DAILY_XML.replace("rate='1.5494'", "rate='abc'");
+
+        let error = exchange.load_ecb_rates_from_str(&bad_rate).unwrap_err();
+        assert!(matches!(error, RateFileError::InvalidRate { line: 14, ref value } if value == "abc"));
+        assert!(exchange.get_rate_on(NaiveDate::from_ymd_opt(2021, 1, 29).unwrap(), Currency::EUR, Currency::USD).is_err());
+
+        let unknown_currency = ECB_DAILY_XML.replace("currency='CAD' rate='1.5533'", "currency='XXY' rate='1.5533'");
+        let error = exchange.load_ecb_rates_from_str(&unknown_currency).unwrap_err();
+        assert!(matches!(error, RateFileError::UnknownCurrency { line: 10, ref code } if code == "XXY"));
+
+        let error = exchange.load_ecb_rates_from_str("<Cube>\n<Cube currency='USD' rate='1.2'/>").unwrap_err();
+        assert!(matches!(error, RateFileError::MissingField { line: 2, field: "time" }));
+
+        let error = exchange.load_ecb_rates_from_str("<Cube>\n<Cube time='2021-01-01'").unwrap_err();
+        assert!(matches!(error, RateFileError::MalformedXml { line: 2, .. }));
+    }
+
+    #[test]
+    fn can_load_rate_files_from_disk(){
+        let directory = std::env::temp_dir();
+        let csv_path = directory.join(format!("simple_money_rates_{}.csv", std::process::id()));
+        let xml_path = directory.join(format!("simple_money_rates_{}.xml", std::process::id()));
+        std::fs::write(&csv_path, "2021-01-28,USD,CAD,1.2797\n").unwrap();
+        std::fs::write(&xml_path, ECB_DAILY_XML).unwrap();
+
+        let mut exchange = Exchange::new();
+        let loaded_csv = exchange.load_csv_rates(&csv_path);
+        let loaded_xml = exchange.load_ecb_rates(&xml_path);
+        std::fs::remove_file(&csv_path).unwrap();
+        std::fs::remove_file(&xml_path).unwrap();
+
+        assert_eq!(loaded_csv.unwrap(), 1);
+        assert_eq!(loaded_xml.unwrap(), 4);
+        assert!(matches!(exchange.load_csv_rates(&csv_path).unwrap_err(), RateFileError::Io(_)));
+    }
+
+    #[test]
+    fn convert_charges_spread_and_fees(){
+        let mut exchange = setup();
+        exchange.set_conversion_costs(Currency::USD, Currency::CAD, ConversionCosts {
+            spread: dec!(0.01),
+            percentage_fee: dec!(0.02),
+            fixed_fee: Some(init_cad_money(dec!(5))),
+        }).unwrap();
+
+        let conversion = exchange.convert(init_usd_money(dec!(1000)), Currency::CAD).unwrap();
+        assert_eq!(conversion.fees.spread, init_cad_money(dec!(6.50)));
+        assert_eq!(conversion.fees.percentage_fee, init_cad_money(dec!(25.87)));
+        assert_eq!(conversion.fees.fixed_fee, init_cad_money(dec!(5)));
+        assert_eq!(conversion.fees.total(), init_cad_money(dec!(37.37)));
+        assert_eq!(conversion.converted, init_cad_money(dec!(1262.63)));
+        assert_eq!(conversion.converted + conversion.fees.total(), exchange.convert_at_mid_rate(init_usd_money(dec!(1000)), Currency::CAD).unwrap());
+
+        let uncharged = exchange.convert(init_cad_money(dec!(1300)), Currency::USD).unwrap();
+        assert_eq!(uncharged.converted, init_usd_money(dec!(1000)));
+        assert_eq!(uncharged.fees.total(), init_zero_amount(Currency::USD));
+    }
+
+    #[test]
+    fn fixed_fees_can_be_charged_in_the_source_currency(){
+        let mut exchange = setup();
+        exchange.set_conversion_costs(Currency::USD, Currency::CAD, ConversionCosts {
+            fixed_fee: Some(init_usd_money(dec!(10))),
+            ..ConversionCosts::default()
+        }).unwrap();
+
+        let conversion = exchange.convert(init_usd_money(dec!(110)), Currency::CAD).unwrap();
+        assert_eq!(conversion.fees.fixed_fee, init_cad_money(dec!(13)));
+        assert_eq!(conversion.converted, init_cad_money(dec!(130)));
+
+        let invalid = exchange.set_conversion_costs(Currency::USD, Currency::CAD, ConversionCosts {
+            fixed_fee: Some(init_eur_money(dec!(1))),
+            ..ConversionCosts::default()
+        });
+        assert_eq!(invalid.unwrap_err(), MoneyError::MismatchedCurrencies);
+    }
+
+    #[test]
+    fn rejects_negative_costs_and_fees_larger_than_the_amount(){
+        let mut exchange = setup();
+        let negative_costs = [
+            ConversionCosts { spread: dec!(-0.5), ..ConversionCosts::default() },
+            ConversionCosts { percentage_fee: dec!(-0.1), ..ConversionCosts::default() },
+            ConversionCosts { fixed_fee: Some(init_usd_money(dec!(-1))), ..ConversionCosts::default() },
+        ];
+        for costs in negative_costs {
+            let invalid = exchange.set_conversion_costs(Currency::USD, Currency::CAD, costs);
+            assert!(matches!(invalid, Err(MoneyError::InvalidConversionCosts(_))));
+        }
+        assert_eq!(exchange.conversion_costs(Currency::USD, Currency::CAD), ConversionCosts::default());
+
+        exchange.set_conversion_costs(Currency::USD, Currency::CAD, ConversionCosts {
+            fixed_fee: Some(init_usd_money(dec!(50))),
+            ..ConversionCosts::default()
+        }).unwrap();
+        assert_eq!(exchange.convert(init_usd_money(dec!(10)), Currency::CAD).unwrap_err(), MoneyError::FeesExceedAmount(init_cad_money(dec!(65))));
+        assert_eq!(exchange.convert(init_usd_money(dec!(50)), Currency::CAD).unwrap().converted, init_zero_amount(Currency::CAD));
+
+        exchange.set_conversion_costs(Currency::USD, Currency::CAD, ConversionCosts {
+            spread: dec!(0.02),
+            fixed_fee: Some(init_cad_money(dec!(20))),
+            ..ConversionCosts::default()
+        }).unwrap();
+        assert_eq!(exchange.convert(init_usd_money(dec!(10)), Currency::CAD).unwrap_err(), MoneyError::FeesExceedAmount(init_cad_money(dec!(20.13))));
+    }
+
+    #[test]
+    fn comparisons_use_the_mid_rate_regardless_of_costs(){
+        let mut exchange = setup();
+        exchange.set_conversion_costs(Currency::CAD, Currency::USD, ConversionCosts {
+            spread: dec!(0.05),
+            ..ConversionCosts::default()
+        }).unwrap();
+
+        assert!(exchange.eq(init_usd_money(dec!(1)), init_cad_money(dec!(1.3))).unwrap());
+    }
+
+    #[test]
+    fn money_bag_keeps_a_balance_per_currency(){
+        let mut bag = MoneyBag::new();
+        bag += init_usd_money(dec!(100));
+        bag += init_cad_money(dec!(50));
+        bag += init_usd_money(dec!(25));
+        bag -= init_eur_money(dec!(10));
+
+        assert_eq!(bag.balance(Currency::USD), init_usd_money(dec!(125)));
+        assert_eq!(bag.balance(Currency::EUR), init_eur_money(dec!(-10)));
+        assert_eq!(bag.balance(Currency::DKK), init_zero_amount(Currency::DKK));
+        assert_eq!(bag.currencies(), vec![Currency::CAD, Currency::EUR, Currency::USD]);
+
+        bag -= init_eur_money(dec!(-10));
+        assert_eq!(bag.currencies(), vec![Currency::CAD, Currency::USD]);
+        assert_eq!(bag, vec![init_cad_money(dec!(50)), init_usd_money(dec!(125))].into_iter().collect());
+    }
+
+    #[test]
+    fn money_bag_can_be_valued_in_any_currency(){
+        let mut exchange = setup();
+        exchange.set_rate(Currency::EUR, Currency::CAD, dec!(1.5));
+        let bag: MoneyBag = vec![init_usd_money(dec!(100)), init_cad_money(dec!(70)), init_eur_money(dec!(20))].into_iter().collect();
+
+        assert_eq!(bag.value_in(&exchange, Currency::CAD).unwrap(), init_cad_money(dec!(230)));
+        assert_eq!(MoneyBag::new().value_in(&exchange, Currency::CAD).unwrap(), init_zero_amount(Currency::CAD));
+        assert_eq!(bag.value_in(&exchange, Currency::DKK).unwrap_err(), MoneyError::CouldNotFindExchangeRate);
+    }
+
+    #[test]
+    fn money_bag_breaks_down_exposure_by_currency(){
+        let mut exchange = setup();
+        exchange.set_rate(Currency::EUR, Currency::CAD, dec!(1.5));
+        let bag: MoneyBag = vec![init_usd_money(dec!(100)), init_cad_money(dec!(70)), init_eur_money(dec!(20))].into_iter().collect();
+
+        let exposure = bag.exposure(&exchange, Currency::CAD).unwrap();
+
+        assert_eq!(exposure.len(), 3);
+        assert_eq!(exposure[0], Exposure { balance: init_cad_money(dec!(70)), value: init_cad_money(dec!(70)), share: dec!(70) / dec!(230) });
+        assert_eq!(exposure[1].balance, init_eur_money(dec!(20)));
+        assert_eq!(exposure[1].value, init_cad_money(dec!(30)));
+        assert_eq!(exposure[2].value, init_cad_money(dec!(130)));
+        assert_eq!(exposure.iter().map(|item| item.share).sum::<Decimal>().round_dp(10), dec!(1));
+    }
+
+    #[test]
+    fn money_bag_and_exchange_helpers_report_overflow(){
+        let exchange = setup();
+        let max_usd = init_usd_money(Decimal::MAX);
+        let min_usd = init_usd_money(Decimal::MIN);
+
+        let mut bag = MoneyBag::new();
+        bag.add(max_usd).unwrap();
+        assert_eq!(bag.add(init_usd_money(dec!(1))).unwrap_err(), MoneyError::Overflow);
+        assert_eq!(bag.sub(init_usd_money(dec!(-1))).unwrap_err(), MoneyError::Overflow);
+        assert_eq!(bag.balance(Currency::USD), max_usd);
+        bag.add(init_cad_money(Decimal::MAX)).unwrap();
+        assert_eq!(bag.value_in(&exchange, Currency::USD).unwrap_err(), MoneyError::Overflow);
+        assert_eq!(bag.exposure(&exchange, Currency::USD).unwrap_err(), MoneyError::Overflow);
+
+        assert_eq!(exchange.add(max_usd, max_usd, Currency::USD).unwrap_err(), MoneyError::Overflow);
+        assert_eq!(exchange.sub(min_usd, max_usd, Currency::USD).unwrap_err(), MoneyError::Overflow);
+        assert_eq!(exchange.add(init_cad_money(Decimal::MAX), max_usd, Currency::USD).unwrap_err(), MoneyError::Overflow);
+        assert_eq!(exchange.sub(init_cad_money(Decimal::MIN), max_usd, Currency::USD).unwrap_err(), MoneyError::Overflow);
+    }
+
+    #[test]
+    fn can_build_rates_from_percent_and_basis_points(){
+        let rate = Rate::from_percent(dec!(5.25));
+
+        assert_eq!(rate, Rate::from_basis_points(dec!(525)));
+        assert_eq!(rate, Rate::from_fraction(dec!(0.0525)));
+        assert_eq!(rate.as_basis_points(), dec!(525));
+        assert_eq!(rate.to_string(), "5.25%");
+        assert_eq!(Rate::from_percent(dec!(1)) + Rate::from_basis_points(dec!(50)), Rate::from_percent(dec!(1.5)));
+        assert_eq!([Rate::from_percent(dec!(10)), Rate::from_percent(dec!(20))].iter().sum::<Rate>(), Rate::from_percent(dec!(30)));
+    }
+
+    #[test]
+    fn can_apply_rates_to_money(){
+        let rate = Rate::from_percent(dec!(15));
+
+        assert_eq!(init_cad_money(dec!(200)) * rate, init_cad_money(dec!(30)));
+        assert_eq!(rate * init_cad_money(dec!(200)), init_cad_money(dec!(30)));
+    }
+
+    #[test]
+    fn can_convert_between_apr_and_apy(){
+        let apr = Rate::from_percent(dec!(12));
+        let apy = apr.to_effective_annual(Compounding::Monthly).unwrap();
+
+        assert_eq!(apy.as_fraction().round_dp(6), dec!(0.126825));
+        assert_eq!(apy.to_nominal_annual(Compounding::Monthly).unwrap().as_fraction().round_dp(6), dec!(0.12));
+        assert_eq!(apr.to_effective_annual(Compounding::Annually).unwrap(), apr);
+        assert_eq!(apr.per_period(Compounding::Monthly), Some(Rate::from_percent(dec!(1))));
+        assert_eq!(Rate::from_periodic(Rate::from_percent(dec!(1)), Compounding::Monthly), Some(apr));
+        assert_eq!(apr.per_period(Compounding::Continuous), None);
+
+        let continuous = Rate::from_percent(dec!(5)).to_effective_annual(Compounding::Continuous).unwrap();
+        assert_eq!(continuous.as_fraction().round_dp(6), dec!(0.051271));
+        assert_eq!(continuous.to_nominal_annual(Compounding::Continuous).unwrap().as_fraction().round_dp(6), dec!(0.05));
+
+        let quarterly = apr.convert_compounding(Compounding::Monthly, Compounding::Quarterly).unwrap();
+        assert_eq!(quarterly.as_fraction().round_dp(6), dec!(0.121204));
+    }
+
+    #[test]
+    fn rejects_rates_that_wipe_out_the_balance(){
+        let rate = Rate::from_percent(dec!(-100));
+
+        assert_eq!(rate.to_nominal_annual(Compounding::Monthly).unwrap_err(), MoneyError::InvalidRate(rate));
+        assert_eq!(Rate::from_percent(dec!(-1200)).to_effective_annual(Compounding::Monthly).unwrap_err(), MoneyError::InvalidRate(Rate::from_percent(dec!(-1200))));
+    }
+
+    #[test]
+    fn time_value_functions_match_spreadsheet_values(){
+        let monthly = Rate::from_percent(dec!(8)).per_period(Compounding::Monthly).unwrap();
+        let zero = init_usd_money(dec!(0));
+
+        let payment = tvm::pmt(monthly, dec!(10), init_usd_money(dec!(10_000)), zero, PaymentTiming::End).unwrap();
+        assert_eq!(payment.round_to_minor_unit(RoundingPolicy::HalfUp), init_usd_money(dec!(-1_037.03)));
+        let payment = tvm::pmt(monthly, dec!(10), init_usd_money(dec!(10_000)), zero, PaymentTiming::Beginning).unwrap();
+        assert_eq!(payment.round_to_minor_unit(RoundingPolicy::HalfUp), init_usd_money(dec!(-1_030.16)));
+        let savings = tvm::pmt(Rate::from_percent(dec!(6)).per_period(Compounding::Monthly).unwrap(), dec!(216), zero, init_usd_money(dec!(50_000)), PaymentTiming::End).unwrap();
+        assert_eq!(savings.round_to_minor_unit(RoundingPolicy::HalfUp), init_usd_money(dec!(-129.08)));
+
+        let present_value = tvm::pv(monthly, dec!(240), init_usd_money(dec!(500)), zero, PaymentTiming::End).unwrap();
+        assert_eq!(present_value.round_to_minor_unit(RoundingPolicy::HalfUp), init_usd_money(dec!(-59_777.15)));
+
+        let future_value = tvm::fv(Rate::from_percent(dec!(6)).per_period(Compounding::Monthly).unwrap(), dec!(10), init_usd_money(dec!(-200)), init_usd_money(dec!(-500)), PaymentTiming::Beginning).unwrap();
+        assert_eq!(future_value.round_to_minor_unit(RoundingPolicy::HalfUp), init_usd_money(dec!(2_581.40)));
+
+        let periods = tvm::nper(Rate::from_percent(dec!(1)), init_usd_money(dec!(-100)), init_usd_money(dec!(-1_000)), init_usd_money(dec!(10_000)), PaymentTiming::Beginning).unwrap();
+        assert_eq!(periods.round_dp(6), dec!(59.673866));
+        let periods = tvm::nper(Rate::from_percent(dec!(1)), init_usd_money(dec!(-100)), init_usd_money(dec!(-1_000)), zero, PaymentTiming::End).unwrap();
+        assert_eq!(periods.round_dp(6), dec!(-9.578594));
+
+        let rate = tvm::rate(dec!(48), init_usd_money(dec!(-200)), init_usd_money(dec!(8_000)), zero, PaymentTiming::End, None).unwrap();
+        assert_eq!(rate.as_fraction().round_dp(8), dec!(0.00770147));
+        let rate = tvm::rate(dec!(60), init_usd_money(dec!(-250)), init_usd_money(dec!(10_000)), zero, PaymentTiming::Beginning, Some(Rate::from_percent(dec!(1)))).unwrap();
+        assert_eq!(rate.as_fraction().round_dp(8), dec!(0.01497064));
+    }
+
+    #[test]
+    fn time_value_functions_handle_a_zero_rate(){
+        let zero = init_usd_money(dec!(0));
+
+        assert_eq!(tvm::pmt(Rate::zero(), dec!(10), init_usd_money(dec!(1_000)), zero, PaymentTiming::End).unwrap(), init_usd_money(dec!(-100)));
+        assert_eq!(tvm::fv(Rate::zero(), dec!(10), init_usd_money(dec!(-100)), zero, PaymentTiming::Beginning).unwrap(), init_usd_money(dec!(1_000)));
+        assert_eq!(tvm::nper(Rate::zero(), init_usd_money(dec!(-100)), init_usd_money(dec!(1_000)), zero, PaymentTiming::End).unwrap(), dec!(10));
+        let rate = tvm::rate(dec!(10), init_usd_money(dec!(-100)), init_usd_money(dec!(1_000)), zero, PaymentTiming::End, None).unwrap();
+        assert_eq!(rate.as_fraction().round_dp(8), dec!(0));
+    }
+
+    #[test]
+    fn time_value_functions_report_unsolvable_inputs(){
+        let zero = init_usd_money(dec!(0));
+
+        let no_rate = tvm::rate(dec!(10), init_usd_money(dec!(100)), init_usd_money(dec!(1_000)), zero, PaymentTiming::End, None);
+        assert_eq!(no_rate.unwrap_err(), MoneyError::DidNotConverge(100));
+        let never_paid_off = tvm::nper(Rate::from_percent(dec!(1)), init_usd_money(dec!(-5)), init_usd_money(dec!(1_000)), zero, PaymentTiming::End);
+        assert_eq!(never_paid_off.unwrap_err(), MoneyError::NoSolution);
+        assert_eq!(tvm::pmt(Rate::from_percent(dec!(1)), dec!(0), init_usd_money(dec!(1_000)), zero, PaymentTiming::End).unwrap_err(), MoneyError::NoSolution);
+        assert_eq!(tvm::pv(Rate::from_percent(dec!(1)), dec!(10), init_cad_money(dec!(100)), zero, PaymentTiming::End).unwrap_err(), MoneyError::MismatchedCurrencies);
+    }
+
+    #[test]
+    fn time_value_functions_report_overflow_instead_of_panicking(){
+        let zero = init_usd_money(dec!(0));
+        let huge = init_usd_money(dec!(-1_000_000_000_000_000_000));
+
+        let tiny_guess = Some(Rate::from_fraction(dec!(0.000000000000001)));
+        let rate = tvm::rate(dec!(10), zero, init_usd_money(dec!(-1_000)), init_usd_money(dec!(1_000)), PaymentTiming::End, tiny_guess);
+        assert_eq!(rate.unwrap_err(), MoneyError::DidNotConverge(100));
+
+        assert_eq!(tvm::pmt(Rate::from_percent(dec!(10)), dec!(500), huge, zero, PaymentTiming::End).unwrap_err(), MoneyError::Overflow);
+        assert_eq!(tvm::fv(Rate::from_percent(dec!(10)), dec!(500), zero, huge, PaymentTiming::End).unwrap_err(), MoneyError::Overflow);
+        assert_eq!(tvm::pv(Rate::from_percent(dec!(10)), dec!(10), init_usd_money(Decimal::MAX), zero, PaymentTiming::End).unwrap_err(), MoneyError::Overflow);
+        assert_eq!(tvm::nper(Rate::zero(), init_usd_money(dec!(0.0000000001)), init_usd_money(Decimal::MAX), zero, PaymentTiming::End).unwrap_err(), MoneyError::Overflow);
+    }
+
+    #[test]
+    fn typed_money_round_trips_through_money(){
+        use std::convert::TryFrom;
+        let typed: TypedMoney<typed::Cad> = TypedMoney::new(dec!(12.50));
+
+        assert_eq!(typed.currency(), Currency::CAD);
+        assert_eq!(Money::from(typed), init_cad_money(dec!(12.50)));
+        assert_eq!(TypedMoney::<typed::Cad>::try_from(init_cad_money(dec!(12.50))).unwrap(), typed);
+        assert_eq!(TypedMoney::<typed::Usd>::try_from(init_cad_money(dec!(12.50))).unwrap_err(), MoneyError::MismatchedCurrencies);
+        assert_eq!(std::mem::size_of::<TypedMoney<typed::Cad>>(), std::mem::size_of::<Decimal>());
+    }
+
+    #[test]
+    fn typed_money_arithmetic_keeps_the_currency(){
+        let mut total: TypedMoney<typed::Cad> = vec![TypedMoney::new(dec!(10)), TypedMoney::new(dec!(5))].into_iter().sum();
+        total -= TypedMoney::new(dec!(3));
+
+        assert_eq!(total, TypedMoney::new(dec!(12)));
+        assert_eq!(total * dec!(2) - TypedMoney::new(dec!(4)), TypedMoney::new(dec!(20)));
+        assert_eq!(total * Rate::from_percent(dec!(50)), TypedMoney::new(dec!(6)));
+        assert!(total > TypedMoney::new(dec!(11)));
+        assert_eq!(total.to_string(), init_cad_money(dec!(12)).to_string());
+    }
+
+    #[test]
+    fn exchange_converts_typed_money(){
+        let exchange = setup();
+        let usd: TypedMoney<typed::Usd> = TypedMoney::new(dec!(100));
+
+        let cad: TypedMoney<typed::Cad> = exchange.convert_typed(usd).unwrap();
+        assert_eq!(cad, TypedMoney::new(dec!(130)));
+        let mid: TypedMoney<typed::Cad> = exchange.convert_typed_at_mid_rate(usd).unwrap();
+        assert_eq!(mid.to_money(), exchange.convert_at_mid_rate(init_usd_money(dec!(100)), Currency::CAD).unwrap());
+        let unknown: Result<TypedMoney<typed::Jpy>, MoneyError> = exchange.convert_typed(usd);
+        assert_eq!(unknown.unwrap_err(), MoneyError::CouldNotFindExchangeRate);
+    }
+
+    struct CountingProvider {
+        rate: Decimal,
+        lookups: std::cell::Cell<u32>,
+    }
+
+    impl RateProvider for CountingProvider {
+        fn rate(&self, from: Currency, to: Currency) -> Result<Decimal, MoneyError> {
+            self.lookups.set(self.lookups.get() + 1);
+            if from == Currency::EUR && to == Currency::CAD {
+                Ok(self.rate)
+            }else{
+                Err(MoneyError::CouldNotFindExchangeRate)
+            }
+        }
+    }
+
+    #[test]
+    fn fallback_provider_uses_the_first_rate_found(){
+        let exchange = setup();
+        let constants = CountingProvider { rate: dec!(1.5), lookups: std::cell::Cell::new(0) };
+        let mut provider = FallbackProvider::new();
+        provider.add_provider(&exchange);
+        provider.add_provider(&constants);
+
+        assert_eq!(provider.rate(Currency::USD, Currency::CAD).unwrap(), dec!(1.3));
+        assert_eq!(constants.lookups.get(), 0);
+        assert_eq!(provider.rate(Currency::EUR, Currency::CAD).unwrap(), dec!(1.5));
+        assert_eq!(provider.rate(Currency::GBP, Currency::CAD).unwrap_err(), MoneyError::CouldNotFindExchangeRate);
+        assert_eq!(FallbackProvider::new().rate(Currency::EUR, Currency::CAD).unwrap_err(), MoneyError::CouldNotFindExchangeRate);
+    }
+
+    #[test]
+    fn caching_provider_asks_once_per_pair(){
+        let provider = CachingProvider::new(CountingProvider { rate: dec!(1.5), lookups: std::cell::Cell::new(0) });
+
+        assert_eq!(provider.rate(Currency::EUR, Currency::CAD).unwrap(), dec!(1.5));
+        assert_eq!(provider.rate(Currency::EUR, Currency::CAD).unwrap(), dec!(1.5));
+        assert_eq!(provider.provider().lookups.get(), 1);
+
+        provider.rate(Currency::GBP, Currency::CAD).unwrap_err();
+        provider.rate(Currency::GBP, Currency::CAD).unwrap_err();
+        assert_eq!(provider.provider().lookups.get(), 3);
+
+        provider.clear();
+        provider.rate(Currency::EUR, Currency::CAD).unwrap();
+        assert_eq!(provider.provider().lookups.get(), 4);
+    }
+
+    #[test]
+    fn override_provider_pins_chosen_pairs(){
+        let mut provider = OverrideProvider::new(setup());
+        provider.set_rate_and_inverse(Currency::EUR, Currency::CAD, dec!(2)).unwrap();
+        assert_eq!(provider.set_rate_and_inverse(Currency::EUR, Currency::USD, dec!(0)).unwrap_err(), MoneyError::InvalidRate(Rate::zero()));
+        assert_eq!(provider.rate(Currency::EUR, Currency::USD).unwrap_err(), MoneyError::CouldNotFindExchangeRate);
+
+        assert_eq!(provider.rate(Currency::EUR, Currency::CAD).unwrap(), dec!(2));
+        assert_eq!(provider.rate(Currency::CAD, Currency::EUR).unwrap(), dec!(0.5));
+        assert_eq!(provider.rate_on(NaiveDate::from_ymd_opt(2020, 1, 1).unwrap(), Currency::EUR, Currency::CAD).unwrap(), dec!(2));
+        assert_eq!(provider.rate(Currency::USD, Currency::CAD).unwrap(), dec!(1.3));
+
+        provider.remove_rate(Currency::EUR, Currency::CAD);
+        assert_eq!(provider.rate(Currency::EUR, Currency::CAD).unwrap_err(), MoneyError::CouldNotFindExchangeRate);
+    }
+
+    #[test]
+    fn shock_provider_moves_a_currency_against_all_others(){
+        let mut exchange = setup();
+        exchange.set_rate(Currency::CAD, Currency::USD, dec!(0.75));
+        let mut provider = ShockProvider::new(exchange);
+        provider.set_shock(Currency::CAD, Rate::from_percent(dec!(-10))).unwrap();
+
+        assert_eq!(provider.rate(Currency::CAD, Currency::USD).unwrap(), dec!(0.675));
+        assert_eq!(provider.rate(Currency::USD, Currency::CAD).unwrap(), dec!(1.3) / dec!(0.9));
+        assert_eq!(provider.set_shock(Currency::USD, Rate::from_percent(dec!(-100))).unwrap_err(), MoneyError::InvalidRate(Rate::from_percent(dec!(-100))));
+
+        provider.set_shock(Currency::USD, Rate::from_percent(dec!(20))).unwrap();
+        assert_eq!(provider.rate(Currency::CAD, Currency::USD).unwrap(), dec!(0.5625));
+
+        provider.set_shock(Currency::CAD, Rate::from_fraction(Decimal::MAX)).unwrap();
+        assert_eq!(provider.rate(Currency::CAD, Currency::USD).unwrap_err(), MoneyError::Overflow);
+        provider.set_shock(Currency::CAD, Rate::from_percent(dec!(1_000))).unwrap();
+        provider.set_shock(Currency::USD, Rate::from_fraction(dec!(-0.9999999999999999999999999999))).unwrap();
+        assert_eq!(provider.rate(Currency::CAD, Currency::USD).unwrap_err(), MoneyError::Overflow);
+    }
+
+    #[test]
+    fn helpers_accept_any_provider(){
+        let mut provider = ShockProvider::new(setup());
+        provider.set_shock(Currency::USD, Rate::from_percent(dec!(-50))).unwrap();
+
+        assert_eq!(provider.convert_at_mid_rate(init_usd_money(dec!(10)), Currency::CAD).unwrap(), init_cad_money(dec!(6.50)));
+        assert!(provider.gt(init_cad_money(dec!(7)), init_usd_money(dec!(10))).unwrap());
+        assert_eq!(provider.add(init_usd_money(dec!(10)), init_cad_money(dec!(1)), Currency::CAD).unwrap(), init_cad_money(dec!(7.50)));
+
+        let bag: MoneyBag = vec![init_usd_money(dec!(10)), init_cad_money(dec!(1))].into_iter().collect();
+        assert_eq!(bag.value_in(&provider, Currency::CAD).unwrap(), init_cad_money(dec!(7.50)));
+    }
+
+    #[test]
+    fn fixed_money_converts_losslessly(){
+        let money = init_usd_money(dec!(-1234.56));
+        let fixed = FixedMoney::from_money(money).unwrap();
+
+        assert_eq!(fixed.minor_units(), -123_456);
+        assert_eq!(fixed.to_money(), money);
+        assert_eq!(FixedMoney::from_money(Money { amount: dec!(1500), currency: Currency::JPY }).unwrap().minor_units(), 1500);
+        assert_eq!(FixedMoney::new(1_500, Currency::KWD).to_money(), Money { amount: dec!(1.500), currency: Currency::KWD });
+        assert_eq!(FixedMoney::from_money(init_usd_money(dec!(1.005))).unwrap_err(), MoneyError::NotWholeMinorUnits(init_usd_money(dec!(1.005))));
+        assert_eq!(FixedMoney::from_money(init_usd_money(dec!(100_000_000_000_000_000))).unwrap_err(), MoneyError::Overflow);
+    }
+
+    #[test]
+    fn fixed_money_arithmetic_is_checked(){
+        let ten = FixedMoney::new(1_000, Currency::USD);
+
+        assert_eq!(ten.checked_add(FixedMoney::new(250, Currency::USD)).unwrap(), FixedMoney::new(1_250, Currency::USD));
+        assert_eq!(ten.checked_sub(FixedMoney::new(1_250, Currency::USD)).unwrap(), FixedMoney::new(-250, Currency::USD));
+        assert_eq!(ten.checked_mul(3).unwrap().checked_neg().unwrap(), FixedMoney::new(-3_000, Currency::USD));
+        assert_eq!(FixedMoney::checked_sum(Currency::USD, vec![ten, ten, ten]).unwrap(), FixedMoney::new(3_000, Currency::USD));
+        assert!(ten > FixedMoney::zero(Currency::USD));
+
+        assert_eq!(ten.checked_add(FixedMoney::new(1_000, Currency::CAD)).unwrap_err(), MoneyError::MismatchedCurrencies);
+        assert_eq!(FixedMoney::new(i64::MAX, Currency::USD).checked_add(FixedMoney::new(1, Currency::USD)).unwrap_err(), MoneyError::Overflow);
+        assert_eq!(FixedMoney::new(i64::MIN, Currency::USD).checked_neg().unwrap_err(), MoneyError::Overflow);
+        assert_eq!(ten.checked_mul(i64::MAX).unwrap_err(), MoneyError::Overflow);
+    }
+
+    #[test]
+    fn checked_arithmetic_reports_overflow_and_division_by_zero(){
+        let max = init_usd_money(Decimal::MAX);
+
+        assert_eq!(max.checked_add(init_usd_money(dec!(1))).unwrap_err(), MoneyError::Overflow);
+        assert_eq!(max.checked_neg().unwrap().checked_sub(init_usd_money(dec!(1))).unwrap_err(), MoneyError::Overflow);
+        assert_eq!(max.checked_mul(dec!(2)).unwrap_err(), MoneyError::Overflow);
+        assert_eq!(max.checked_mul(2_i64).unwrap_err(), MoneyError::Overflow);
+        assert_eq!(max.checked_mul(Rate::from_percent(dec!(200))).unwrap_err(), MoneyError::Overflow);
+        assert_eq!(max.checked_div(dec!(0.5)).unwrap_err(), MoneyError::Overflow);
+        assert_eq!(init_usd_money(dec!(10)).checked_div(dec!(0)).unwrap_err(), MoneyError::DivisionByZero);
+        assert_eq!(init_usd_money(dec!(10)).checked_div(init_usd_money(dec!(0))).unwrap_err(), MoneyError::DivisionByZero);
+        assert_eq!(init_usd_money(dec!(10)).checked_div(init_cad_money(dec!(5))).unwrap_err(), MoneyError::MismatchedCurrencies);
+
+        assert_eq!(init_usd_money(dec!(10)).checked_mul(3_i32).unwrap(), init_usd_money(dec!(30)));
+        assert_eq!(init_usd_money(dec!(10)).checked_div(dec!(4)).unwrap(), init_usd_money(dec!(2.5)));
+        assert_eq!(init_usd_money(dec!(10)).checked_div(init_usd_money(dec!(4))).unwrap(), dec!(2.5));
+        assert_eq!(init_usd_money(dec!(10)).checked_neg().unwrap(), init_usd_money(dec!(-10)));
+    }
+
+    #[test]
+    fn sums_check_every_amount(){
+        let amounts = vec![init_usd_money(dec!(10)), init_usd_money(dec!(2.50))];
+
+        assert_eq!(amounts.iter().sum::<Result<Money, MoneyError>>().unwrap(), init_usd_money(dec!(12.50)));
+        assert_eq!(Money::checked_sum(Currency::USD, amounts.clone()).unwrap(), init_usd_money(dec!(12.50)));
+        assert_eq!(Money::checked_sum(Currency::USD, vec![]).unwrap(), init_zero_amount(Currency::USD));
+        assert_eq!(Vec::<Money>::new().into_iter().sum::<Result<Money, MoneyError>>().unwrap_err(), MoneyError::EmptySum);
+        assert_eq!(Money::checked_sum(Currency::CAD, amounts.clone()).unwrap_err(), MoneyError::MismatchedCurrencies);
+
+        let mixed = vec![init_usd_money(dec!(10)), init_cad_money(dec!(10))];
+        assert_eq!(mixed.into_iter().sum::<Result<Money, MoneyError>>().unwrap_err(), MoneyError::MismatchedCurrencies);
+        let huge = vec![init_usd_money(Decimal::MAX), init_usd_money(Decimal::MAX)];
+        assert_eq!(huge.into_iter().sum::<Result<Money, MoneyError>>().unwrap_err(), MoneyError::Overflow);
+    }
+
+    #[test]
+    fn conversion_costs_report_overflow_instead_of_panicking(){
+        let mut exchange = setup();
+        exchange.set_rate(Currency::CAD, Currency::USD, dec!(0.5));
+        exchange.set_conversion_costs(Currency::CAD, Currency::USD, ConversionCosts {
+            spread: dec!(0.01),
+            percentage_fee: dec!(0.02),
+            fixed_fee: Some(init_cad_money(dec!(1))),
+        }).unwrap();
+
+        assert_eq!(exchange.convert(init_cad_money(Decimal::MAX), Currency::USD).unwrap().fees.fixed_fee, init_usd_money(dec!(0.50)));
+        exchange.set_rate(Currency::CAD, Currency::USD, dec!(2));
+        assert_eq!(exchange.convert(init_cad_money(Decimal::MAX), Currency::USD).unwrap_err(), MoneyError::Overflow);
+    }
+
+    #[test]
+    fn currency_table_matches_the_generated_currencies(){
+        let records = currency_table::parse(include_str!("../data/currencies.csv")).unwrap();
+
+        assert_eq!(records.len(), Currency::all().len());
+        for (record, currency) in records.iter().zip(Currency::all()) {
+            assert_eq!(record.code, currency.code());
+            assert_eq!(record.name, currency.name());
+        }
+        assert!(currency_table::generate(&records).contains("Currency::ANG => ANG_CURRENCY_DATA,"));
+        assert_eq!(Currency::ANG.match_currency_to_data().symbol, "NAƒ");
+        assert_ne!(Currency::ANG.match_currency_to_data().symbol, Currency::AMD.match_currency_to_data().symbol);
+        assert_eq!(Currency::GBP.name(), "Pound Sterling");
+    }
+
+    #[test]
+    fn currency_table_rejects_duplicate_and_inconsistent_entries(){
+        let header = "code,numeric_code,name,exponent,cash_increment,symbol,symbol_first,locale\n";
+        let parse = |rows: &str| currency_table::parse(&format!("{}{}", header, rows));
+
+        assert!(parse("CAD,124,Canadian Dollar,2,5,$,true,USA\nUSD,840,US Dollar,2,1,$,true,USA\n").is_ok());
+        assert_eq!(parse("CAD,124,Canadian Dollar,2,5,$,true,USA\nCAD,124,Canadian Dollar,2,5,$,true,USA\n").unwrap_err(), "line 3: CAD is listed twice");
+        assert_eq!(parse("USD,840,US Dollar,2,1,$,true,USA\nCAD,124,Canadian Dollar,2,5,$,true,USA\n").unwrap_err(), "line 3: CAD must come before USD");
+        assert_eq!(parse("CAD,840,Canadian Dollar,2,5,$,true,USA\nUSD,840,US Dollar,2,1,$,true,USA\n").unwrap_err(), "line 3: numeric code 840 is already used by CAD");
+        assert_eq!(parse("CAD,124,Dollar,2,5,$,true,USA\nUSD,840,Dollar,2,1,$,true,USA\n").unwrap_err(), "line 3: the name Dollar is already used by CAD");
+        assert_eq!(parse("CAD,124,Canadian Dollar,2,5,$,true\n").unwrap_err(), "line 2: expected 8 fields but found 7");
+        assert_eq!(parse("CAD,124,Canadian Dollar,2,5,,true,USA\n").unwrap_err(), "line 2: fields can't be empty or start or end with spaces");
+        assert_eq!(parse("Cad,124,Canadian Dollar,2,5,$,true,USA\n").unwrap_err(), "line 2: Cad is not three upper case letters");
+        assert_eq!(parse("CAD,1240,Canadian Dollar,2,5,$,true,USA\n").unwrap_err(), "line 2: 1240 is not a numeric code from 1 to 999");
+        assert_eq!(parse("CAD,124,Canadian Dollar,5,5,$,true,USA\n").unwrap_err(), "line 2: 5 is not an exponent from 0 to 4");
+        assert_eq!(parse("CAD,124,Canadian Dollar,2,0,$,true,USA\n").unwrap_err(), "line 2: 0 is not a cash increment of at least 1");
+        assert_eq!(parse("CAD,124,Canadian Dollar,2,5,$,yes,USA\n").unwrap_err(), "line 2: symbol_first must be true or false, not yes");
+        assert!(parse("CAD,124,Canadian Dollar,2,5,$,true,Canada\n").unwrap_err().starts_with("line 2: Canada is not one of the locales"));
+        assert_eq!(currency_table::parse("code,name\n").unwrap_err(), format!("line 1: expected the header {}", header.trim_end()));
+    }
+
+    #[test]
+    fn changeover_table_matches_the_generated_changeovers(){
+        let records = currency_table::parse(include_str!("../data/currencies.csv")).unwrap();
+        let changeovers = currency_table::parse_changeovers(include_str!("../data/changeovers.csv"), &records).unwrap();
+
+        assert_eq!(changeovers.len(), Currency::all().iter().filter(|currency| currency.changeover().is_some()).count());
+        for record in &changeovers {
+            let changeover = record.from.parse::<Currency>().unwrap().changeover().unwrap();
+            let (year, month, day) = record.date;
+            assert_eq!(changeover.to.code(), record.to);
+            assert_eq!(changeover.date, NaiveDate::from_ymd_opt(year, month, day).unwrap());
+            assert_eq!(changeover.factor, record.factor.parse::<Decimal>().unwrap());
+        }
+        assert_eq!(Currency::ATS.changeover().unwrap().factor, dec!(13.7603));
+        assert!(currency_table::generate_changeovers(&changeovers).contains("ChangeoverData { from: Currency::ATS, to: Currency::EUR, date: (1999, 1, 1), factor: dec!(13.7603) },"));
+        assert!(currency_table::generate_currency_types(&records).contains("    Cad => CAD,\n"));
+        assert_eq!(<typed::Zwr as typed::CurrencyType>::currency(), Currency::ZWR);
+    }
+
+    #[test]
+    fn changeover_table_rejects_unknown_currencies_and_inconsistent_entries(){
+        let records = currency_table::parse(include_str!("../data/currencies.csv")).unwrap();
+        let header = "from,to,date,factor\n";
+        let parse = |rows: &str| currency_table::parse_changeovers(&format!("{}{}", header, rows), &records);
+
+        assert!(parse("ZWD,ZWN,2006-08-01,1000\nZWN,ZWR,2008-08-01,10000000000\n").is_ok());
+        assert_eq!(parse("XYZ,EUR,1999-01-01,1\n").unwrap_err(), "line 2: XYZ is not in the currency table");
+        assert_eq!(parse("EUR,EUR,1999-01-01,1\n").unwrap_err(), "line 2: EUR can't be replaced by itself");
+        assert_eq!(parse("ATS,EUR,1999-02-29,13.7603\n").unwrap_err(), "line 2: 1999-02-29 is not a date in the YYYY-MM-DD format");
+        assert_eq!(parse("ATS,EUR,1999-1-1,13.7603\n").unwrap_err(), "line 2: 1999-1-1 is not a date in the YYYY-MM-DD format");
+        assert!(parse("ATS,EUR,2000-02-29,13.7603\n").is_ok());
+        assert_eq!(parse("ATS,EUR,1999-01-01,0.000\n").unwrap_err(), "line 2: 0.000 is not a positive decimal factor");
+        assert_eq!(parse("ATS,EUR,1999-01-01,-13.7603\n").unwrap_err(), "line 2: -13.7603 is not a positive decimal factor");
+        assert_eq!(parse("ATS,EUR,1999-01-01,13.76.03\n").unwrap_err(), "line 2: 13.76.03 is not a positive decimal factor");
+        assert_eq!(parse("ATS,EUR,1999-01-01,13.7603\nATS,DEM,1999-01-01,7\n").unwrap_err(), "line 3: ATS is already replaced on line 2");
+        assert_eq!(parse("ZWD,ZWN,2008-08-01,1000\nZWN,ZWR,2006-08-01,1000\n").unwrap_err(), "line 2: ZWN is replaced by ZWR before ZWD replaces it");
+        assert_eq!(parse("ATS,DEM,1999-01-01,7\nDEM,ATS,2001-01-01,7\n").unwrap_err(), "line 3: ATS is replaced by DEM before DEM replaces it");
+        assert_eq!(parse("ATS,EUR,1999-01-01\n").unwrap_err(), "line 2: expected 4 fields but found 3");
+        assert_eq!(currency_table::parse_changeovers("from,to,factor\n", &records).unwrap_err(), "line 1: expected the header from,to,date,factor");
+    }
+
+    #[test]
+    fn dated_provider_uses_the_rates_on_its_date(){
+        let mut exchange = setup();
+        let first_day = NaiveDate::from_ymd_opt(2021, 1, 4).unwrap();
+        let second_day = NaiveDate::from_ymd_opt(2021, 1, 5).unwrap();
+        exchange.set_rate_and_inverse_on(first_day, Currency::USD, Currency::CAD, dec!(1.25));
+        exchange.set_rate_and_inverse_on(second_day, Currency::USD, Currency::CAD, dec!(1.4));
+
+        assert_eq!(exchange.rates_on(first_day).convert_at_mid_rate(init_usd_money(dec!(10)), Currency::CAD).unwrap(), init_cad_money(dec!(12.50)));
+        assert_eq!(exchange.rates_on(second_day).add(init_usd_money(dec!(10)), init_cad_money(dec!(1)), Currency::CAD).unwrap(), init_cad_money(dec!(15.00)));
+        assert!(exchange.rates_on(first_day).lt(init_usd_money(dec!(10)), init_cad_money(dec!(13))).unwrap());
+        assert!(exchange.rates_on(second_day).gt(init_usd_money(dec!(10)), init_cad_money(dec!(13))).unwrap());
+        assert_eq!(exchange.rates_on(first_day).date(), first_day);
+    }
+
+    #[cfg(feature = "simulation")]
+    fn simulation(seed: u64) -> FxSimulation {
+        FxSimulation::new(NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(), chrono::Duration::days(1), 250, seed)
+    }
+
+    #[cfg(feature = "simulation")]
+    #[test]
+    fn fx_simulation_is_reproducible_from_its_seed(){
+        let model = FxModel::GeometricBrownianMotion { drift: Rate::from_percent(dec!(2)), volatility: Rate::from_percent(dec!(10)) };
+        let mut first = simulation(7);
+        first.add_pair(Currency::USD, Currency::CAD, dec!(1.3), model.clone()).unwrap();
+        let mut second = simulation(7);
+        second.add_pair(Currency::USD, Currency::CAD, dec!(1.3), model.clone()).unwrap();
+        second.add_pair(Currency::EUR, Currency::USD, dec!(1.1), model.clone()).unwrap();
+        let mut third = simulation(8);
+        third.add_pair(Currency::USD, Currency::CAD, dec!(1.3), model).unwrap();
+
+        let first_paths = first.generate().unwrap();
+        let second_paths = second.generate().unwrap();
+        assert_eq!(first_paths, first.generate().unwrap());
+        assert_eq!(first_paths[0], second_paths[0]);
+        assert_ne!(first_paths[0], third.generate().unwrap()[0]);
+        assert_ne!(second_paths[0].rates.values().nth(1), second_paths[1].rates.values().nth(1));
+        assert_eq!(first_paths[0].rates.len(), 251);
+        assert_eq!(first_paths[0].rates[&NaiveDate::from_ymd_opt(2021, 1, 1).unwrap()], dec!(1.3));
+    }
+
+    #[cfg(feature = "simulation")]
+    #[test]
+    fn fx_simulation_follows_its_models(){
+        let mut simulation = simulation(1);
+        simulation.add_pair(Currency::USD, Currency::CAD, dec!(1.3), FxModel::GeometricBrownianMotion { drift: Rate::from_percent(dec!(5)), volatility: Rate::from_percent(dec!(0)) }).unwrap();
+        simulation.add_pair(Currency::EUR, Currency::USD, dec!(1.5), FxModel::OrnsteinUhlenbeck { long_run_rate: dec!(1.1), reversion_speed: dec!(20), volatility: Rate::from_percent(dec!(1)) }).unwrap();
+        let history = vec![dec!(100), dec!(110), dec!(100)];
+        simulation.add_pair(Currency::GBP, Currency::JPY, dec!(150), FxModel::BlockBootstrap { history, block_length: 2 }).unwrap();
+        let paths = simulation.generate().unwrap();
+
+        let after_a_year = NaiveDate::from_ymd_opt(2021, 12, 31).unwrap() + chrono::Duration::days(1);
+        let mut gbm = FxSimulation::new(NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(), chrono::Duration::days(365), 1, 1);
+        gbm.add_pair(Currency::USD, Currency::CAD, dec!(1.3), FxModel::GeometricBrownianMotion { drift: Rate::from_percent(dec!(5)), volatility: Rate::from_percent(dec!(0)) }).unwrap();
+        let expected = dec!(1.3) * rust_decimal::MathematicalOps::exp(&dec!(0.05));
+        assert!((gbm.generate().unwrap()[0].rates[&after_a_year] - expected).abs() < dec!(0.000001));
+        assert!(paths[0].rates.values().zip(paths[0].rates.values().skip(1)).all(|(today, tomorrow)| tomorrow > today));
+
+        let last_rate = *paths[1].rates.values().next_back().unwrap();
+        assert!((last_rate - dec!(1.1)).abs() < dec!(0.05));
+
+        // The only block in the history rises 10% and falls back, so every block returns to where it started.
+        let bootstrap = &paths[2].rates;
+        assert!(bootstrap.values().step_by(2).all(|rate| (*rate - dec!(150)).abs() < dec!(0.0001)));
+        assert!(bootstrap.values().skip(1).step_by(2).all(|rate| (*rate - dec!(165)).abs() < dec!(0.0001)));
+    }
+
+    #[cfg(feature = "simulation")]
+    #[test]
+    fn fx_simulation_rejects_invalid_pairs(){
+        let gbm = FxModel::GeometricBrownianMotion { drift: Rate::from_percent(dec!(0)), volatility: Rate::from_percent(dec!(10)) };
+        let mut simulation = simulation(1);
+
+        assert!(matches!(simulation.add_pair(Currency::USD, Currency::USD, dec!(1), gbm.clone()), Err(MoneyError::InvalidSimulation(_))));
+        assert!(matches!(simulation.add_pair(Currency::USD, Currency::CAD, dec!(0), gbm), Err(MoneyError::InvalidSimulation(_))));
+        let negative_volatility = FxModel::GeometricBrownianMotion { drift: Rate::from_percent(dec!(0)), volatility: Rate::from_percent(dec!(-10)) };
+        assert!(matches!(simulation.add_pair(Currency::USD, Currency::CAD, dec!(1.3), negative_volatility), Err(MoneyError::InvalidSimulation(_))));
+        let short_history = FxModel::BlockBootstrap { history: vec![dec!(1.3), dec!(1.31)], block_length: 2 };
+        assert!(matches!(simulation.add_pair(Currency::USD, Currency::CAD, dec!(1.3), short_history), Err(MoneyError::InvalidSimulation(_))));
+        let mut hourly = FxSimulation::new(NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(), chrono::Duration::hours(1), 10, 1);
+        hourly.add_pair(Currency::USD, Currency::CAD, dec!(1.3), FxModel::GeometricBrownianMotion { drift: Rate::from_percent(dec!(0)), volatility: Rate::from_percent(dec!(10)) }).unwrap();
+        assert!(matches!(hourly.generate(), Err(MoneyError::InvalidSimulation(_))));
+    }
+
+    #[cfg(feature = "simulation")]
+    #[test]
+    fn fx_simulation_loads_into_an_exchange(){
+        let mut simulation = simulation(3);
+        simulation.add_pair(Currency::USD, Currency::CAD, dec!(1.3), FxModel::GeometricBrownianMotion { drift: Rate::from_percent(dec!(0)), volatility: Rate::from_percent(dec!(15)) }).unwrap();
+        let path = simulation.generate().unwrap().remove(0);
+        let mut exchange = setup();
+        simulation.load_into(&mut exchange).unwrap();
+
+        let date = NaiveDate::from_ymd_opt(2021, 6, 1).unwrap();
+        let rate = path.rates[&date];
+        let ten_usd = init_usd_money(dec!(10));
+        assert_eq!(exchange.get_rate_on(date, Currency::USD, Currency::CAD).unwrap(), rate);
+        assert_eq!(exchange.convert_on(date, ten_usd, Currency::CAD).unwrap().converted, init_cad_money((dec!(10) * rate).round_dp(2)));
+        assert_eq!(exchange.rates_on(date).convert_at_mid_rate(init_cad_money(dec!(10)), Currency::USD).unwrap(), init_usd_money((dec!(10) / rate).round_dp(2)));
+        assert!(exchange.rates_on(date).lt(ten_usd, Money { amount: dec!(10) * rate + dec!(0.01), currency: Currency::CAD }).unwrap());
+        assert_eq!(exchange.convert(ten_usd, Currency::CAD).unwrap().converted, init_cad_money(dec!(13)));
+    }
+
+    #[cfg(feature = "simulation")]
+    #[test]
+    fn fx_simulation_keeps_small_rates_positive(){
+        let start = NaiveDate::from_ymd_opt(2021, 1, 1).unwrap();
+        let mut simulation = FxSimulation::new(start, chrono::Duration::days(30), 120, 1);
+        simulation.add_pair(Currency::VND, Currency::XAU, dec!(0.000000017), FxModel::GeometricBrownianMotion { drift: Rate::from_percent(dec!(-80)), volatility: Rate::from_percent(dec!(10)) }).unwrap();
+        let path = simulation.generate().unwrap().remove(0);
+        let last_rate = *path.rates.values().next_back().unwrap();
+        assert!(last_rate > dec!(0) && last_rate < dec!(0.0000000001));
+        assert!(path.rates.values().all(|rate| rate.mantissa().abs() < 10_000_000_000));
+
+        let mut exchange = setup();
+        simulation.load_into(&mut exchange).unwrap();
+        assert_eq!(exchange.get_rate_on(start + chrono::Duration::days(3_600), Currency::VND, Currency::XAU).unwrap(), last_rate);
+
+        let mut collapse = FxSimulation::new(start, chrono::Duration::days(30), 120, 1);
+        collapse.add_pair(Currency::VND, Currency::XAU, dec!(0.000000017), FxModel::GeometricBrownianMotion { drift: Rate::from_percent(dec!(-100000)), volatility: Rate::from_percent(dec!(10)) }).unwrap();
+        assert!(matches!(collapse.generate(), Err(MoneyError::InvalidSimulation(_))));
+        assert!(matches!(collapse.load_into(&mut exchange), Err(MoneyError::InvalidSimulation(_))));
+
+        let mut zero_rate = path;
+        zero_rate.rates.insert(start, dec!(0));
+        assert_eq!(setup().load_fx_path(&zero_rate).unwrap_err(), MoneyError::InvalidRate(Rate::from_fraction(dec!(0))));
+    }
+
+    #[cfg(feature = "simulation")]
+    #[test]
+    fn fx_simulation_reports_rates_it_cannot_keep_and_stops_at_its_last_date(){
+        let mut volatile = FxSimulation::new(NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(), chrono::Duration::days(365), 10, 1);
+        volatile.add_pair(Currency::USD, Currency::CAD, dec!(1.3), FxModel::GeometricBrownianMotion { drift: Rate::from_percent(dec!(0)), volatility: Rate::from_fraction(dec!(40)) }).unwrap();
+        assert!(matches!(volatile.generate(), Err(MoneyError::InvalidSimulation(_))));
+
+        let mut last_days = FxSimulation::new(NaiveDate::MAX - chrono::Duration::days(10), chrono::Duration::days(5), 2, 1);
+        last_days.add_pair(Currency::USD, Currency::CAD, dec!(1.3), FxModel::GeometricBrownianMotion { drift: Rate::from_percent(dec!(0)), volatility: Rate::from_percent(dec!(10)) }).unwrap();
+        let path = last_days.generate().unwrap().remove(0);
+        assert_eq!(path.rates.keys().next_back(), Some(&NaiveDate::MAX));
+        assert_eq!(path.rates.len(), 3);
+    }
+
+    #[test]
+    fn rate_matrix_keeps_values_as_it_grows(){
+        let custom = Currency::register("MTXPTS", 0, "pts", false, Locale::USA).unwrap();
+        let mut matrix = RateMatrix::new();
+        matrix.insert(Currency::USD, Currency::CAD, dec!(1.3));
+        matrix.insert(Currency::EUR, Currency::USD, dec!(1.1));
+        matrix.insert(custom, Currency::CAD, dec!(0.01));
+        *matrix.get_or_insert_with(Currency::CAD, Currency::CAD, || dec!(0)) += dec!(1);
+
+        assert_eq!(matrix.get(Currency::USD, Currency::CAD), Some(&dec!(1.3)));
+        assert_eq!(matrix.get(Currency::EUR, Currency::USD), Some(&dec!(1.1)));
+        assert_eq!(matrix.get(custom, Currency::CAD), Some(&dec!(0.01)));
+        assert_eq!(matrix.get(Currency::CAD, Currency::CAD), Some(&dec!(1)));
+        assert_eq!(matrix.get(Currency::CAD, Currency::USD), None);
+        assert_eq!(matrix.get(Currency::GBP, Currency::USD), None);
+        assert_eq!(matrix.insert(Currency::USD, Currency::CAD, dec!(1.4)), Some(dec!(1.3)));
+        assert_eq!(matrix.currencies(), &[Currency::USD, Currency::CAD, Currency::EUR, custom]);
+        assert_eq!(matrix.iter().count(), 4);
+        assert_ne!(custom.index(), Currency::ZWR.index());
+    }
+
+    #[test]
+    fn exchange_snapshot_matches_the_exchange(){
+        let mut exchange = setup();
+        exchange.set_rate_and_inverse(Currency::EUR, Currency::USD, dec!(1.1));
+        exchange.set_rate_on(NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(), Currency::USD, Currency::CAD, dec!(1.25));
+        exchange.set_conversion_costs(Currency::USD, Currency::CAD, ConversionCosts { spread: dec!(0.01), ..ConversionCosts::default() }).unwrap();
+        let snapshot = exchange.snapshot();
+        let date = NaiveDate::from_ymd_opt(2021, 1, 1).unwrap();
+        let ten_usd = init_usd_money(dec!(10));
+
+        for (from, to) in [(Currency::USD, Currency::CAD), (Currency::EUR, Currency::CAD), (Currency::CAD, Currency::EUR), (Currency::CAD, Currency::CAD)] {
+            assert_eq!(snapshot.get_rate(from, to), exchange.get_rate(from, to));
+            assert_eq!(snapshot.conversion_path(from, to), exchange.conversion_path(from, to));
+        }
+        assert_eq!(snapshot.get_rate(Currency::GBP, Currency::CAD), Err(MoneyError::CouldNotFindExchangeRate));
+        assert_eq!(snapshot.get_rate_on(date, Currency::USD, Currency::CAD), Ok(dec!(1.25)));
+        assert_eq!(snapshot.convert(ten_usd, Currency::CAD), exchange.convert(ten_usd, Currency::CAD));
+        assert_eq!(snapshot.convert_on(date, ten_usd, Currency::CAD), exchange.convert_on(date, ten_usd, Currency::CAD));
+        assert_eq!(snapshot.rates_on(date).convert_at_mid_rate(ten_usd, Currency::CAD).unwrap(), init_cad_money(dec!(12.50)));
+        assert!(snapshot.lt(ten_usd, init_cad_money(dec!(13.01))).unwrap());
+
+        exchange.set_rate(Currency::USD, Currency::CAD, dec!(2));
+        assert_eq!(snapshot.get_rate(Currency::USD, Currency::CAD), Ok(dec!(1.3)));
+    }
+
+    #[test]
+    fn exchange_snapshot_can_be_shared_between_threads(){
+        let snapshot = std::sync::Arc::new(setup().snapshot());
+        let handles: Vec<_> = (1..=4).map(|dollars| {
+            let snapshot = std::sync::Arc::clone(&snapshot);
+            std::thread::spawn(move || snapshot.convert(init_usd_money(Decimal::from(dollars)), Currency::CAD).unwrap().converted)
+        }).collect();
+
+        let converted: Vec<Money> = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+        assert_eq!(converted, [init_cad_money(dec!(1.30)), init_cad_money(dec!(2.60)), init_cad_money(dec!(3.90)), init_cad_money(dec!(5.20))]);
+    }
+
+    #[test]
+    fn validate_accepts_consistent_rates(){
+        let mut exchange = setup();
+        exchange.set_rate_and_inverse(Currency::EUR, Currency::USD, dec!(1.1));
+        exchange.set_rate(Currency::EUR, Currency::CAD, dec!(1.43));
+        exchange.set_rate_and_inverse_on(NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(), Currency::USD, Currency::CAD, dec!(1.25));
+
+        let report = exchange.validate();
+        assert!(report.is_valid(), "{}", report);
+        assert_eq!(report.into_result(), Ok(()));
+    }
+
+    #[test]
+    fn validate_reports_bad_rates(){
+        let date = NaiveDate::from_ymd_opt(2021, 1, 1).unwrap();
+        let mut exchange = setup();
+        exchange.set_rate(Currency::CAD, Currency::USD, dec!(0.8));
+        exchange.set_rate_and_inverse(Currency::EUR, Currency::USD, dec!(1.1));
+        exchange.set_rate(Currency::EUR, Currency::CAD, dec!(1.5));
+        exchange.set_rate(Currency::GBP, Currency::CAD, dec!(0));
+        exchange.set_rate_on(date, Currency::USD, Currency::CAD, dec!(-1.3));
+        let options = ValidationOptions { required_currencies: vec![Currency::USD, Currency::JPY], ..ValidationOptions::default() };
+
+        let report = exchange.validate_with(&options);
+        let issues = report.issues();
+        assert!(issues.contains(&RateIssue::NonPositiveRate { from: Currency::GBP, to: Currency::CAD, date: None, rate: dec!(0) }));
+        assert!(issues.contains(&RateIssue::NonPositiveRate { from: Currency::USD, to: Currency::CAD, date: Some(date), rate: dec!(-1.3) }));
+        assert!(issues.contains(&RateIssue::InconsistentInverse { from: Currency::CAD, to: Currency::USD, date: None, rate: dec!(0.8), inverse_rate: dec!(1.3) }));
+        assert!(issues.contains(&RateIssue::MissingPair { from: Currency::USD, to: Currency::JPY }));
+        assert!(issues.contains(&RateIssue::MissingPair { from: Currency::JPY, to: Currency::USD }));
+        let cycles: Vec<&RateIssue> = issues.iter().filter(|issue| matches!(issue, RateIssue::ArbitrageCycle { .. })).collect();
+        assert_eq!(cycles.len(), 1);
+        // 1 USD buys 1 / 1.1 EUR, which buys 1.5 / 1.1 CAD, which buys 0.8 * 1.5 / 1.1 USD.
+        let expected_gain = dec!(1) / dec!(1.1) * dec!(1.5) * dec!(0.8) - dec!(1);
+        assert_eq!(cycles[0], &RateIssue::ArbitrageCycle { currencies: vec![Currency::USD, Currency::EUR, Currency::CAD, Currency::USD], gain: Rate::from_fraction(expected_gain) });
+        assert_eq!(issues.len(), 6);
+        assert!(report.to_string().contains("the GBP/CAD rate is 0"));
+        assert!(matches!(report.clone().into_result(), Err(MoneyError::InvalidRates(rejected)) if rejected == report));
+
+        let loose = ValidationOptions { tolerance: Rate::from_percent(dec!(10)), ..ValidationOptions::default() };
+        assert_eq!(exchange.validate_with(&loose).issues().len(), 2);
+    }
+
+    #[cfg(feature = "simulation")]
+    #[test]
+    fn fx_simulation_refuses_invalid_exchanges(){
+        let mut simulation = simulation(1);
+        simulation.add_pair(Currency::USD, Currency::CAD, dec!(1.3), FxModel::GeometricBrownianMotion { drift: Rate::zero(), volatility: Rate::from_percent(dec!(10)) }).unwrap();
+        let mut exchange = setup();
+        exchange.set_rate(Currency::CAD, Currency::USD, dec!(0.5));
+
+        assert!(matches!(simulation.load_into(&mut exchange), Err(MoneyError::InvalidRates(_))));
+        assert_eq!(exchange.get_rate_on(NaiveDate::from_ymd_opt(2021, 1, 2).unwrap(), Currency::USD, Currency::CAD), Ok(dec!(1.3)));
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{init_zero_amount, CheckedAdd, CheckedSub, Currency, Money, MoneyError, RateProvider};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::iter::FromIterator;
+use std::ops::{AddAssign, SubAssign};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Balances held in several currencies at once. Unlike `Money + Money`, adding an amount in a
+/// new currency simply starts a new balance.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MoneyBag {
+    balances: HashMap<Currency, Decimal>,
+}
+
+/// How much of a `MoneyBag`'s value is held in one currency.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Exposure {
+    /// The balance held in the currency.
+    pub balance: Money,
+    /// The balance valued in the currency the breakdown was made in.
+    pub value: Money,
+    /// The value as a fraction of the bag's total value. Zero when the total is zero.
+    pub share: Decimal,
+}
+
+impl MoneyBag {
+    pub fn new() -> MoneyBag {
+        MoneyBag { balances: HashMap::new() }
+    }
+
+    /// Adds `money` to the balance in its currency. Fails with `MoneyError::Overflow`, leaving
+    /// the bag unchanged, if the balance would overflow.
+    pub fn add(&mut self, money: Money) -> Result<(), MoneyError> {
+        let balance = self.balance(money.currency).checked_add(money)?;
+        self.set_balance(balance);
+        Ok(())
+    }
+
+    pub fn sub(&mut self, money: Money) -> Result<(), MoneyError> {
+        let balance = self.balance(money.currency).checked_sub(money)?;
+        self.set_balance(balance);
+        Ok(())
+    }
+
+    fn set_balance(&mut self, balance: Money) {
+        if balance.amount.is_zero() {
+            self.balances.remove(&balance.currency);
+        } else {
+            self.balances.insert(balance.currency, balance.amount);
+        }
+    }
+
+    /// Returns the balance held in `currency`, which is zero if none is held.
+    pub fn balance(&self, currency: Currency) -> Money {
+        match self.balances.get(&currency) {
+            Some(amount) => Money { amount: *amount, currency },
+            None => init_zero_amount(currency),
+        }
+    }
+
+    /// Returns every non-zero balance, ordered by currency code.
+    pub fn balances(&self) -> Vec<Money> {
+        let mut balances: Vec<Money> = self.balances.iter().map(|(currency, amount)| Money { amount: *amount, currency: *currency }).collect();
+        balances.sort_by_key(|balance| balance.currency.code());
+        balances
+    }
+
+    pub fn currencies(&self) -> Vec<Currency> {
+        self.balances().iter().map(|balance| balance.currency).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.balances.is_empty()
+    }
+
+    /// Values every balance in `currency` at the provider's mid rates and adds them up.
+    pub fn value_in<P: RateProvider + ?Sized>(&self, provider: &P, currency: Currency) -> Result<Money, MoneyError> {
+        let mut total = init_zero_amount(currency);
+        for balance in self.balances() {
+            total = total.checked_add(provider.convert_at_mid_rate(balance, currency)?)?;
+        }
+
+        Ok(total)
+    }
+
+    /// Breaks the bag's value in `currency` down by the currency each balance is held in,
+    /// ordered by currency code.
+    pub fn exposure<P: RateProvider + ?Sized>(&self, provider: &P, currency: Currency) -> Result<Vec<Exposure>, MoneyError> {
+        let values = self.balances()
+            .into_iter()
+            .map(|balance| Ok((balance, provider.convert_at_mid_rate(balance, currency)?)))
+            .collect::<Result<Vec<(Money, Money)>, MoneyError>>()?;
+
+        let total = Money::checked_sum(currency, values.iter().map(|(_, value)| *value))?.amount;
+        values
+            .into_iter()
+            .map(|(balance, value)| {
+                let share = if total.is_zero() { Decimal::new(0, 0) } else { value.amount.checked_div(total).ok_or(MoneyError::Overflow)? };
+                Ok(Exposure { balance, value, share })
+            })
+            .collect()
+    }
+}
+
+// Like `Money + Money`, the operators panic where the methods return an error, so use
+// `MoneyBag::add` and `MoneyBag::sub` when a balance could overflow.
+impl AddAssign<Money> for MoneyBag {
+    fn add_assign(&mut self, other: Money) {
+        self.add(other).expect("MoneyBag balance overflowed");
+    }
+}
+
+impl SubAssign<Money> for MoneyBag {
+    fn sub_assign(&mut self, other: Money) {
+        self.sub(other).expect("MoneyBag balance overflowed");
+    }
+}
+
+impl FromIterator<Money> for MoneyBag {
+    fn from_iter<I: IntoIterator<Item = Money>>(iter: I) -> Self {
+        let mut bag = MoneyBag::new();
+        for money in iter {
+            bag += money;
+        }
+        bag
+    }
+}
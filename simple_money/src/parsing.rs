@@ -0,0 +1,194 @@
+use crate::currency::{Locale, ALL_CURRENCIES};
+use crate::formatting::{DigitGrouping, Digits, NumberFormat};
+use crate::{Currency, Money};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(PartialEq, Debug, Error)]
+pub enum MoneyParseError {
+    #[error("Nothing to parse")]
+    Empty,
+    #[error("Could not parse an amount from {0}")]
+    InvalidNumber(String),
+    #[error("Could not find a currency symbol or code in {0}")]
+    MissingCurrency(String),
+    #[error("The symbol {symbol} is used by several currencies: {candidates:?}")]
+    AmbiguousSymbol { symbol: String, candidates: Vec<Currency> },
+    #[error("Expected an amount in {expected} but found {found}")]
+    MismatchedCurrency { expected: Currency, found: Currency },
+}
+
+const MINUS_SIGNS: &[char] = &['-', '\u{2212}'];
+const SPACES: &[char] = &[' ', '\u{00A0}', '\u{202F}'];
+const APOSTROPHES: &[char] = &['\'', '\u{2019}'];
+
+/// Removes a leading or trailing minus sign, or parentheses around the whole text.
+fn take_sign(text: &str) -> (bool, &str) {
+    let text = text.trim();
+    if let Some(inner) = text.strip_prefix('(').and_then(|text| text.strip_suffix(')')) {
+        return (true, inner.trim());
+    }
+    if let Some(rest) = text.strip_prefix(MINUS_SIGNS).or_else(|| text.strip_suffix(MINUS_SIGNS)) {
+        return (true, rest.trim());
+    }
+
+    (false, text)
+}
+
+/// Removes `marker` from the start or end of `text`. Codes are matched case-insensitively.
+fn strip_marker<'a>(text: &'a str, marker: &str) -> Option<&'a str> {
+    if marker.is_empty() {
+        return None;
+    }
+
+    let is_code = marker.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+    let matches = |part: Option<&str>| matches!(part, Some(part) if part == marker || (is_code && part.eq_ignore_ascii_case(marker)));
+    if matches(text.get(..marker.len())) {
+        return Some(text[marker.len()..].trim());
+    }
+    if text.len() >= marker.len() && matches(text.get(text.len() - marker.len()..)) {
+        return Some(text[..text.len() - marker.len()].trim());
+    }
+
+    None
+}
+
+fn strip_currency(text: &str, currency: Currency) -> Option<&str> {
+    let currency_data = currency.match_currency_to_data();
+    let mut markers = [currency_data.code, currency_data.symbol];
+    markers.sort_by_key(|marker| std::cmp::Reverse(marker.len()));
+    markers.iter().find_map(|marker| strip_marker(text, marker))
+}
+
+/// Finds the currency whose code or symbol starts or ends `text`, preferring codes and then the
+/// longest symbol.
+fn detect_currency(text: &str) -> Result<(Currency, &str), MoneyParseError> {
+    let currencies: Vec<Currency> = ALL_CURRENCIES.iter().copied().chain(Currency::custom()).collect();
+    for currency in currencies.iter() {
+        if let Some(rest) = strip_marker(text, currency.code()) {
+            return Ok((*currency, rest));
+        }
+    }
+
+    let mut matches: Vec<(Currency, &str, &str)> = vec![];
+    for currency in currencies.iter() {
+        let symbol = currency.match_currency_to_data().symbol;
+        if let Some(rest) = strip_marker(text, symbol) {
+            matches.push((*currency, symbol, rest));
+        }
+    }
+
+    let longest = matches.iter().map(|(_, symbol, _)| symbol.len()).max().ok_or_else(|| MoneyParseError::MissingCurrency(text.to_string()))?;
+    matches.retain(|(_, symbol, _)| symbol.len() == longest);
+    match matches.as_slice() {
+        [(currency, _, rest)] => Ok((*currency, rest)),
+        _ => Err(MoneyParseError::AmbiguousSymbol {
+            symbol: matches[0].1.to_string(),
+            candidates: matches.iter().map(|(currency, _, _)| *currency).collect(),
+        }),
+    }
+}
+
+fn is_valid_grouping(groups: &[&str], grouping: DigitGrouping) -> bool {
+    let (first_max, middle) = match grouping {
+        DigitGrouping::Thousands => (3, 3),
+        DigitGrouping::Indian => (2, 2),
+        DigitGrouping::None => return groups.len() == 1,
+    };
+
+    let count = |group: &str| group.chars().count();
+    match groups {
+        [] => false,
+        [_] => true,
+        [first, middles @ .., last] => {
+            (1..=first_max).contains(&count(first)) && middles.iter().all(|group| count(group) == middle) && count(last) == 3
+        }
+    }
+}
+
+/// Parses a number written with `format`'s separators and grouping, in any supported digits.
+fn parse_number(text: &str, format: &NumberFormat) -> Result<Decimal, MoneyParseError> {
+    let invalid = || MoneyParseError::InvalidNumber(text.to_string());
+
+    // Spaces and apostrophes are often typed in place of the typographic separators.
+    let mut normalized = text.to_string();
+    for alternatives in [SPACES, APOSTROPHES].iter() {
+        if format.grouping_separator.chars().all(|c| alternatives.contains(&c)) {
+            normalized = normalized.replace(*alternatives, format.grouping_separator);
+        }
+    }
+
+    let mut parts = normalized.splitn(2, format.decimal_separator);
+    let integer = parts.next().unwrap_or_default();
+    let fraction = parts.next();
+    let groups: Vec<&str> = if integer.is_empty() { vec![] } else { integer.split(format.grouping_separator).collect() };
+    if (!groups.is_empty() && !is_valid_grouping(&groups, format.grouping)) || (groups.is_empty() && fraction.is_none()) {
+        return Err(invalid());
+    }
+
+    let mut plain = String::new();
+    for c in groups.concat().chars() {
+        plain.push(Digits::to_latin(c).ok_or_else(invalid)?);
+    }
+    if let Some(fraction) = fraction {
+        plain.push('.');
+        for c in fraction.chars() {
+            plain.push(Digits::to_latin(c).ok_or_else(invalid)?);
+        }
+    }
+
+    Decimal::from_str(&plain).map_err(|_| invalid())
+}
+
+fn parse_signed(text: &str, currency: Currency, locale: Locale, outer_negative: bool) -> Result<Money, MoneyParseError> {
+    let (inner_negative, number) = take_sign(text);
+    if outer_negative && inner_negative {
+        return Err(MoneyParseError::InvalidNumber(text.to_string()));
+    }
+
+    let amount = parse_number(number, &locale.number_format())?;
+    let amount = if outer_negative || inner_negative { -amount } else { amount };
+    Ok(Money { amount, currency })
+}
+
+impl Money {
+    /// Parses an amount of `currency` written in `locale`'s style, such as "€100.000,00" or
+    /// "1,00kr." for `Locale::EU`.
+    ///
+    /// The currency's symbol or ISO code may come before or after the number, or be left out.
+    /// Negative amounts can use a minus sign on either side or parentheses, as in "$(1.00)".
+    /// Grouping separators must be in the places `locale` puts them.
+    pub fn parse(input: &str, currency: Currency, locale: Locale) -> Result<Money, MoneyParseError> {
+        let (negative, text) = take_sign(input);
+        if text.is_empty() {
+            return Err(MoneyParseError::Empty);
+        }
+
+        let number = match strip_currency(text, currency) {
+            Some(number) => number,
+            None => match detect_currency(text) {
+                Ok((found, _)) if found != currency => return Err(MoneyParseError::MismatchedCurrency { expected: currency, found }),
+                _ => text,
+            },
+        };
+        parse_signed(number, currency, locale, negative)
+    }
+}
+
+impl FromStr for Money {
+    type Err = MoneyParseError;
+
+    /// Parses an amount whose currency is given by its ISO code or an unambiguous symbol, using
+    /// that currency's usual locale. "USD 1,234.56", "€100.000,00" and "1 234,50zł" all parse,
+    /// but "$1.00" does not since many currencies use "$".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, text) = take_sign(s);
+        if text.is_empty() {
+            return Err(MoneyParseError::Empty);
+        }
+
+        let (currency, number) = detect_currency(text)?;
+        parse_signed(number, currency, currency.match_currency_to_data().locale, negative)
+    }
+}
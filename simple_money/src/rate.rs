@@ -0,0 +1,192 @@
+use crate::{CheckedMul, Money, MoneyError};
+use rust_decimal::{Decimal, MathematicalOps};
+use rust_decimal_macros::*;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, Mul, Sub};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A rate such as an interest, tax or inclusion rate, stored as a fraction so that 5% is 0.05.
+///
+/// Build one with `from_percent`, `from_basis_points` or `from_fraction` so that it is always
+/// clear which of those a number means.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct Rate(Decimal);
+
+/// How often interest is added to the balance over a year.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Compounding {
+    Annually,
+    SemiAnnually,
+    Quarterly,
+    Monthly,
+    Weekly,
+    Daily,
+    Continuous,
+}
+
+impl Compounding {
+    /// Returns how many times interest is compounded in a year, or `None` for continuous
+    /// compounding.
+    pub fn periods_per_year(&self) -> Option<u32> {
+        match self {
+            Compounding::Annually => Some(1),
+            Compounding::SemiAnnually => Some(2),
+            Compounding::Quarterly => Some(4),
+            Compounding::Monthly => Some(12),
+            Compounding::Weekly => Some(52),
+            Compounding::Daily => Some(365),
+            Compounding::Continuous => None,
+        }
+    }
+}
+
+impl Rate {
+    /// A rate of `fraction`, where 1 is 100%.
+    pub fn from_fraction(fraction: Decimal) -> Rate {
+        Rate(fraction)
+    }
+
+    pub fn from_percent(percent: Decimal) -> Rate {
+        Rate(percent / dec!(100))
+    }
+
+    pub fn from_basis_points(basis_points: Decimal) -> Rate {
+        Rate(basis_points / dec!(10_000))
+    }
+
+    pub fn zero() -> Rate {
+        Rate(Decimal::new(0, 0))
+    }
+
+    pub fn as_fraction(&self) -> Decimal {
+        self.0
+    }
+
+    pub fn as_percent(&self) -> Decimal {
+        self.0 * dec!(100)
+    }
+
+    pub fn as_basis_points(&self) -> Decimal {
+        self.0 * dec!(10_000)
+    }
+
+    /// Treats this as a nominal annual rate (APR) compounded `compounding` and returns the
+    /// effective annual rate (APY) it works out to.
+    pub fn to_effective_annual(&self, compounding: Compounding) -> Result<Rate, MoneyError> {
+        let growth = match compounding.periods_per_year() {
+            Some(periods) => {
+                let base = dec!(1) + self.0 / Decimal::from(periods);
+                if !base.is_sign_positive() || base.is_zero() {
+                    return Err(MoneyError::InvalidRate(*self));
+                }
+                base.checked_powi(periods.into())
+            }
+            None => self.0.checked_exp(),
+        };
+
+        Ok(Rate(growth.ok_or(MoneyError::Overflow)? - dec!(1)))
+    }
+
+    /// Treats this as an effective annual rate (APY) and returns the nominal annual rate (APR)
+    /// that gives the same growth when compounded `compounding`.
+    pub fn to_nominal_annual(&self, compounding: Compounding) -> Result<Rate, MoneyError> {
+        let growth = dec!(1) + self.0;
+        if !growth.is_sign_positive() || growth.is_zero() {
+            return Err(MoneyError::InvalidRate(*self));
+        }
+
+        let nominal = match compounding.periods_per_year() {
+            Some(periods) => {
+                let periods = Decimal::from(periods);
+                let growth_per_period = growth.checked_powd(dec!(1) / periods).ok_or(MoneyError::Overflow)?;
+                (growth_per_period - dec!(1)) * periods
+            }
+            None => growth.checked_ln().ok_or(MoneyError::Overflow)?,
+        };
+
+        Ok(Rate(nominal))
+    }
+
+    /// Re-expresses this nominal annual rate compounded `from` as the nominal annual rate
+    /// compounded `to` with the same effective annual rate.
+    pub fn convert_compounding(&self, from: Compounding, to: Compounding) -> Result<Rate, MoneyError> {
+        if from == to {
+            return Ok(*self);
+        }
+
+        self.to_effective_annual(from)?.to_nominal_annual(to)
+    }
+
+    /// Returns the rate charged each period when this nominal annual rate is compounded
+    /// `compounding`, such as an APR divided by 12 for monthly interest. Continuous
+    /// compounding has no periods.
+    pub fn per_period(&self, compounding: Compounding) -> Option<Rate> {
+        compounding.periods_per_year().map(|periods| Rate(self.0 / Decimal::from(periods)))
+    }
+
+    /// Returns the nominal annual rate for a rate charged each period, the inverse of
+    /// `per_period`.
+    pub fn from_periodic(periodic_rate: Rate, compounding: Compounding) -> Option<Rate> {
+        compounding.periods_per_year().map(|periods| Rate(periodic_rate.0 * Decimal::from(periods)))
+    }
+}
+
+impl fmt::Display for Rate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}%", self.as_percent().normalize())
+    }
+}
+
+impl Add for Rate {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Rate(self.0 + other.0)
+    }
+}
+
+impl Sub for Rate {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Rate(self.0 - other.0)
+    }
+}
+
+impl Sum for Rate {
+    fn sum<I: Iterator<Item = Rate>>(iter: I) -> Self {
+        iter.fold(Rate::zero(), |total, rate| total + rate)
+    }
+}
+
+impl<'a> Sum<&'a Rate> for Rate {
+    fn sum<I: Iterator<Item = &'a Rate>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl Mul<Rate> for Money {
+    type Output = Self;
+
+    fn mul(self, rate: Rate) -> Self::Output {
+        Self { amount: self.amount * rate.0, currency: self.currency }
+    }
+}
+
+impl Mul<Money> for Rate {
+    type Output = Money;
+
+    fn mul(self, money: Money) -> Self::Output {
+        money * self
+    }
+}
+
+impl CheckedMul<Rate> for Money {
+    fn checked_mul(&self, rate: Rate) -> Result<Self, MoneyError> {
+        self.checked_mul(rate.0)
+    }
+}
@@ -0,0 +1,206 @@
+use crate::{Currency, Exchange};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum RateFileError {
+    #[error("Could not read rate file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Line {line}: missing {field}")]
+    MissingField { line: usize, field: &'static str },
+    #[error("Line {line}: could not parse date {value}")]
+    InvalidDate { line: usize, value: String },
+    #[error("Line {line}: could not parse currency {value}")]
+    InvalidCurrency { line: usize, value: String },
+    #[error("Line {line}: unknown currency {code}")]
+    UnknownCurrency { line: usize, code: String },
+    #[error("Line {line}: could not parse rate {value}")]
+    InvalidRate { line: usize, value: String },
+    #[error("Line {line}: malformed XML, {message}")]
+    MalformedXml { line: usize, message: String },
+}
+
+fn parse_date(line: usize, value: &str) -> Result<NaiveDate, RateFileError> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| RateFileError::InvalidDate { line, value: value.to_string() })
+}
+
+fn parse_currency(line: usize, value: &str) -> Result<Currency, RateFileError> {
+    value.parse().map_err(|_| RateFileError::InvalidCurrency { line, value: value.to_string() })
+}
+
+fn parse_rate(line: usize, value: &str) -> Result<Decimal, RateFileError> {
+    match Decimal::from_str(value) {
+        Ok(rate) if rate > Decimal::new(0, 0) => Ok(rate),
+        _ => Err(RateFileError::InvalidRate { line, value: value.to_string() }),
+    }
+}
+
+/// A single `<...>` tag from an XML document, with the line it starts on.
+struct XmlTag<'a> {
+    line: usize,
+    name: &'a str,
+    attributes: Vec<(&'a str, &'a str)>,
+    is_closing: bool,
+}
+
+fn parse_attributes(line: usize, mut text: &str) -> Result<Vec<(&str, &str)>, RateFileError> {
+    let mut attributes = vec![];
+    loop {
+        text = text.trim_start();
+        if text.is_empty() {
+            return Ok(attributes);
+        }
+
+        let equals = text.find('=').ok_or_else(|| RateFileError::MalformedXml { line, message: format!("expected an attribute in {}", text) })?;
+        let name = text[..equals].trim();
+        let rest = text[equals + 1..].trim_start();
+        let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'').ok_or_else(|| RateFileError::MalformedXml {
+            line,
+            message: format!("attribute {} is not quoted", name),
+        })?;
+        let end = rest[1..].find(quote).ok_or_else(|| RateFileError::MalformedXml {
+            line,
+            message: format!("attribute {} is not terminated", name),
+        })?;
+        attributes.push((name, &rest[1..end + 1]));
+        text = &rest[end + 2..];
+    }
+}
+
+fn parse_xml_tags(contents: &str) -> Result<Vec<XmlTag<'_>>, RateFileError> {
+    let mut tags = vec![];
+    let mut position = 0;
+    while let Some(offset) = contents[position..].find('<') {
+        let start = position + offset;
+        let line = contents[..start].matches('\n').count() + 1;
+        let end = start + contents[start..].find('>').ok_or_else(|| RateFileError::MalformedXml {
+            line,
+            message: "tag is not closed".to_string(),
+        })?;
+        position = end + 1;
+
+        let body = &contents[start + 1..end];
+        if body.starts_with('?') || body.starts_with('!') {
+            continue;
+        }
+
+        let is_closing = body.starts_with('/');
+        let body = body.trim_start_matches('/').trim_end_matches('/').trim();
+        let name_end = body.find(char::is_whitespace).unwrap_or(body.len());
+        let name = &body[..name_end];
+        let attributes = parse_attributes(line, &body[name_end..])?;
+        tags.push(XmlTag { line, name, attributes, is_closing });
+    }
+
+    Ok(tags)
+}
+
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/// A rate read from a rate file, set on `date`.
+struct DatedRate {
+    date: NaiveDate,
+    from: Currency,
+    to: Currency,
+    rate: Decimal,
+}
+
+fn parse_csv_rates(contents: &str) -> Result<Vec<DatedRate>, RateFileError> {
+    let mut rates = vec![];
+    for (index, row) in contents.lines().enumerate() {
+        let line = index + 1;
+        let row = row.trim();
+        if row.is_empty() || row.starts_with('#') || (line == 1 && row.to_ascii_lowercase().starts_with("date")) {
+            continue;
+        }
+
+        let mut fields = row.split(',').map(str::trim);
+        let date = fields.next().filter(|field| !field.is_empty()).ok_or(RateFileError::MissingField { line, field: "date" })?;
+        let from = fields.next().filter(|field| !field.is_empty()).ok_or(RateFileError::MissingField { line, field: "from" })?;
+        let to = fields.next().filter(|field| !field.is_empty()).ok_or(RateFileError::MissingField { line, field: "to" })?;
+        let rate = fields.next().filter(|field| !field.is_empty()).ok_or(RateFileError::MissingField { line, field: "rate" })?;
+
+        rates.push(DatedRate {
+            date: parse_date(line, date)?,
+            from: parse_currency(line, from)?,
+            to: parse_currency(line, to)?,
+            rate: parse_rate(line, rate)?,
+        });
+    }
+
+    Ok(rates)
+}
+
+fn parse_ecb_rates(contents: &str) -> Result<Vec<DatedRate>, RateFileError> {
+    let mut rates = vec![];
+    let mut current_date: Option<NaiveDate> = None;
+    for tag in parse_xml_tags(contents)? {
+        if local_name(tag.name) != "Cube" || tag.is_closing {
+            continue;
+        }
+
+        let attribute = |name: &str| tag.attributes.iter().find(|(key, _)| *key == name).map(|(_, value)| *value);
+        if let Some(time) = attribute("time") {
+            current_date = Some(parse_date(tag.line, time)?);
+        }
+
+        if let Some(code) = attribute("currency") {
+            let rate = attribute("rate").ok_or(RateFileError::MissingField { line: tag.line, field: "rate" })?;
+            let date = current_date.ok_or(RateFileError::MissingField { line: tag.line, field: "time" })?;
+            let rate = parse_rate(tag.line, rate)?;
+            let currency = code.parse::<Currency>().map_err(|_| RateFileError::UnknownCurrency { line: tag.line, code: code.to_string() })?;
+            rates.push(DatedRate { date, from: Currency::EUR, to: currency, rate });
+        }
+    }
+
+    Ok(rates)
+}
+
+impl Exchange {
+    /// Loads dated rates from CSV text with `date,from,to,rate` columns, returning how many
+    /// rates were set.
+    ///
+    /// Dates use the `YYYY-MM-DD` format. A header row starting with `date`, blank lines and
+    /// lines starting with `#` are skipped. The whole file is read before any rate is set, so
+    /// nothing is loaded if any line has an error.
+    pub fn load_csv_rates_from_str(&mut self, contents: &str) -> Result<usize, RateFileError> {
+        let rates = parse_csv_rates(contents)?;
+        for DatedRate { date, from, to, rate } in &rates {
+            self.set_rate_on(*date, *from, *to, *rate);
+        }
+
+        Ok(rates.len())
+    }
+
+    pub fn load_csv_rates<P: AsRef<Path>>(&mut self, path: P) -> Result<usize, RateFileError> {
+        let contents = fs::read_to_string(path)?;
+        self.load_csv_rates_from_str(&contents)
+    }
+
+    /// Loads rates in the European Central Bank's daily or historical reference rate XML format,
+    /// returning how many rates were set.
+    ///
+    /// Every `<Cube currency=".." rate=".."/>` is set as a EUR rate on the date of the enclosing
+    /// `<Cube time="..">`, along with its inverse. A currency this crate does not know is an
+    /// error, so register any such currency with `Currency::register` first. As with CSV files,
+    /// nothing is loaded if any line has an error.
+    pub fn load_ecb_rates_from_str(&mut self, contents: &str) -> Result<usize, RateFileError> {
+        let rates = parse_ecb_rates(contents)?;
+        for DatedRate { date, from, to, rate } in &rates {
+            self.set_rate_and_inverse_on(*date, *from, *to, *rate);
+        }
+
+        Ok(rates.len())
+    }
+
+    pub fn load_ecb_rates<P: AsRef<Path>>(&mut self, path: P) -> Result<usize, RateFileError> {
+        let contents = fs::read_to_string(path)?;
+        self.load_ecb_rates_from_str(&contents)
+    }
+}
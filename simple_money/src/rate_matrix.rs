@@ -0,0 +1,100 @@
+use crate::Currency;
+
+/// A value for each ordered pair of currencies, stored in a square table so that looking a pair
+/// up is two array reads rather than a hash.
+///
+/// Only currencies that have been given a value take up a row and a column. `Currency::index`
+/// finds a currency's row, and the table grows by one row and column when a new currency is
+/// added.
+#[derive(Clone, Debug)]
+pub(crate) struct RateMatrix<T> {
+    /// For each `Currency::index`, one more than the currency's row, or 0 if it has none.
+    rows: Vec<usize>,
+    currencies: Vec<Currency>,
+    /// The value for `from` and `to` is at `from_row * currencies.len() + to_row`.
+    entries: Vec<Option<T>>,
+}
+
+impl<T> RateMatrix<T> {
+    pub(crate) fn new() -> RateMatrix<T> {
+        RateMatrix { rows: Vec::new(), currencies: Vec::new(), entries: Vec::new() }
+    }
+
+    fn row(&self, currency: Currency) -> Option<usize> {
+        match self.rows.get(currency.index()) {
+            Some(row) if *row > 0 => Some(*row - 1),
+            _ => None,
+        }
+    }
+
+    fn position(&self, from: Currency, to: Currency) -> Option<usize> {
+        Some(self.row(from)? * self.currencies.len() + self.row(to)?)
+    }
+
+    /// Returns the row of `currency`, adding one if it has none yet.
+    fn row_or_insert(&mut self, currency: Currency) -> usize {
+        if let Some(row) = self.row(currency) {
+            return row;
+        }
+
+        let size = self.currencies.len();
+        let mut entries = Vec::with_capacity((size + 1) * (size + 1));
+        let mut old_entries = self.entries.drain(..);
+        for _ in 0..size {
+            entries.extend(old_entries.by_ref().take(size));
+            entries.push(None);
+        }
+        entries.extend((0..=size).map(|_| None));
+        drop(old_entries);
+        self.entries = entries;
+
+        let index = currency.index();
+        if self.rows.len() <= index {
+            self.rows.resize(index + 1, 0);
+        }
+        self.rows[index] = size + 1;
+        self.currencies.push(currency);
+        size
+    }
+
+    pub(crate) fn get(&self, from: Currency, to: Currency) -> Option<&T> {
+        self.entries[self.position(from, to)?].as_ref()
+    }
+
+    pub(crate) fn insert(&mut self, from: Currency, to: Currency, value: T) -> Option<T> {
+        let from_row = self.row_or_insert(from);
+        let to_row = self.row_or_insert(to);
+        self.entries[from_row * self.currencies.len() + to_row].replace(value)
+    }
+
+    pub(crate) fn get_or_insert_with<F: FnOnce() -> T>(&mut self, from: Currency, to: Currency, value: F) -> &mut T {
+        let from_row = self.row_or_insert(from);
+        let to_row = self.row_or_insert(to);
+        self.entries[from_row * self.currencies.len() + to_row].get_or_insert_with(value)
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.rows.clear();
+        self.currencies.clear();
+        self.entries.clear();
+    }
+
+    /// Every currency that has a row, in the order they were added.
+    pub(crate) fn currencies(&self) -> &[Currency] {
+        &self.currencies
+    }
+
+    /// Every pair that has a value, in the order of their rows and then their columns.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (Currency, Currency, &T)> + '_ {
+        let size = self.currencies.len();
+        self.entries.iter().enumerate().filter_map(move |(position, entry)| {
+            entry.as_ref().map(|value| (self.currencies[position / size], self.currencies[position % size], value))
+        })
+    }
+}
+
+impl<T> Default for RateMatrix<T> {
+    fn default() -> RateMatrix<T> {
+        RateMatrix::new()
+    }
+}
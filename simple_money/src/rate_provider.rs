@@ -0,0 +1,464 @@
+use crate::{CheckedAdd, CheckedSub, Currency, Exchange, ExchangeRateQuery, Money, MoneyError, Rate, RoundingPolicy};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal_macros::*;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// A source of exchange rates. A rate from `from` to `to` is how many units of `to` one unit of
+/// `from` buys.
+///
+/// `Exchange` is the usual provider, and the wrappers in this module change how another
+/// provider's rates are found: `FallbackProvider` asks several in turn, `CachingProvider`
+/// remembers answers, `OverrideProvider` pins chosen pairs and `ShockProvider` moves a
+/// currency's value against all others. The conversion and comparison helpers work with any
+/// provider.
+pub trait RateProvider {
+    fn rate(&self, from: Currency, to: Currency) -> Result<Decimal, MoneyError>;
+
+    /// Returns the rate on `date`. Providers without a rate history use the same rate every day.
+    fn rate_on(&self, _date: NaiveDate, from: Currency, to: Currency) -> Result<Decimal, MoneyError> {
+        self.rate(from, to)
+    }
+
+    /// How converted amounts, and the results of `add`, `sub` and `clamp`, are rounded.
+    fn rounding_policy(&self) -> RoundingPolicy {
+        RoundingPolicy::default()
+    }
+
+    /// Converts `money` at the mid rate, ignoring any spread or fees set for the pair. This is
+    /// what `add`, `sub`, `clamp` and the comparison helpers use to value amounts.
+    fn convert_at_mid_rate(&self, money: Money, currency: Currency) -> Result<Money, MoneyError> {
+        if money.currency == currency {
+            return Ok(money);
+        }
+
+        let rate = self.rate(money.currency, currency)?;
+        let amount = money.amount.checked_mul(rate).ok_or(MoneyError::Overflow)?;
+        Ok(round(self, Money { amount, currency }))
+    }
+
+    fn add(&self, first: Money, second: Money, output_currency: Currency) -> Result<Money, MoneyError> {
+        if first.currency == output_currency && second.currency == output_currency {
+            Ok(round(self, first.checked_add(second)?))
+        }else{
+            let first_in_output_currency = self.convert_at_mid_rate(first, output_currency)?;
+            let second_in_output_currency = self.convert_at_mid_rate(second, output_currency)?;
+            Ok(round(self, first_in_output_currency.checked_add(second_in_output_currency)?))
+        }
+    }
+
+    fn sub(&self, first: Money, second: Money, output_currency: Currency) -> Result<Money, MoneyError> {
+        if first.currency == output_currency && second.currency == output_currency {
+            Ok(round(self, first.checked_sub(second)?))
+        }else{
+            let first_in_output_currency = self.convert_at_mid_rate(first, output_currency)?;
+            let second_in_output_currency =self.convert_at_mid_rate(second, output_currency)?;
+            Ok(round(self, first_in_output_currency.checked_sub(second_in_output_currency)?))
+        }
+    }
+
+    fn lt(&self, first: Money, second: Money) -> Result<bool, MoneyError> {
+        if first.currency == second.currency {
+            Ok(first < second)
+        }else{
+            let second_in_first_currency = self.convert_at_mid_rate(second, first.currency)?;
+            Ok(first < second_in_first_currency)
+        }
+    }
+
+    fn lte(&self, first: Money, second: Money) -> Result<bool, MoneyError> {
+        if first.currency == second.currency {
+            Ok(first <= second)
+        }else{
+            let second_in_first_currency = self.convert_at_mid_rate(second, first.currency)?;
+            Ok(first <= second_in_first_currency)
+        }
+    }
+    
+    fn eq(&self, first: Money, second: Money) -> Result<bool, MoneyError> {
+        if first.currency == second.currency {
+            Ok(first == second)
+        }else{
+            let second_in_first_currency = self.convert_at_mid_rate(second, first.currency)?;
+            Ok(first == second_in_first_currency)
+        }
+    }
+
+    fn gte(&self, first: Money, second: Money) -> Result<bool, MoneyError> {
+        if first.currency == second.currency {
+            Ok(first >= second)
+        }else{
+            let second_in_first_currency = self.convert_at_mid_rate(second, first.currency)?;
+            Ok(first >= second_in_first_currency)
+        }
+    }
+
+    fn gt(&self, first: Money, second: Money) -> Result<bool, MoneyError> {
+        if first.currency == second.currency {
+            Ok(first > second)
+        }else{
+            let second_in_first_currency = self.convert_at_mid_rate(second, first.currency)?;
+            Ok(first > second_in_first_currency)
+        }
+    }
+
+    /// Clamps `input` between `min` and `max` after converting all three into
+    /// `output_currency`, so each can be in any currency. Fails with `MoneyError::InvalidRange`
+    /// if `min` is more than `max` once converted.
+    fn clamp(&self, input: Money, min: Money, max: Money, output_currency: Currency) -> Result<Money, MoneyError>{
+        let input_in_output_currency = self.convert_at_mid_rate(input, output_currency)?;
+        let min_in_output_currency = self.convert_at_mid_rate(min, output_currency)?;
+        let max_in_output_currency = self.convert_at_mid_rate(max, output_currency)?;
+        if min_in_output_currency > max_in_output_currency {
+            return Err(MoneyError::InvalidRange(min_in_output_currency, max_in_output_currency));
+        }
+
+        Ok(round(self, input_in_output_currency.clamp(min_in_output_currency, max_in_output_currency)))
+    }
+}
+
+fn round<P: RateProvider + ?Sized>(provider: &P, money: Money) -> Money {
+    money.round_to_minor_unit(provider.rounding_policy())
+}
+
+impl RateProvider for Exchange {
+    fn rate(&self, from: Currency, to: Currency) -> Result<Decimal, MoneyError> {
+        self.get_rate(from, to)
+    }
+
+    fn rate_on(&self, date: NaiveDate, from: Currency, to: Currency) -> Result<Decimal, MoneyError> {
+        self.get_rate_on(date, from, to)
+    }
+
+    fn rounding_policy(&self) -> RoundingPolicy {
+        Exchange::rounding_policy(self)
+    }
+}
+
+// The helpers are also kept as inherent methods, so code written before `RateProvider` existed
+// keeps working without importing the trait.
+impl Exchange {
+    pub fn convert_at_mid_rate(&self, money: Money, currency: Currency) -> Result<Money, MoneyError> {
+        RateProvider::convert_at_mid_rate(self, money, currency)
+    }
+
+    pub fn add(&self, first: Money, second: Money, output_currency: Currency) -> Result<Money, MoneyError> {
+        RateProvider::add(self, first, second, output_currency)
+    }
+
+    pub fn sub(&self, first: Money, second: Money, output_currency: Currency) -> Result<Money, MoneyError> {
+        RateProvider::sub(self, first, second, output_currency)
+    }
+
+    pub fn lt(&self, first: Money, second: Money) -> Result<bool, MoneyError> {
+        RateProvider::lt(self, first, second)
+    }
+
+    pub fn lte(&self, first: Money, second: Money) -> Result<bool, MoneyError> {
+        RateProvider::lte(self, first, second)
+    }
+
+    pub fn eq(&self, first: Money, second: Money) -> Result<bool, MoneyError> {
+        RateProvider::eq(self, first, second)
+    }
+
+    pub fn gte(&self, first: Money, second: Money) -> Result<bool, MoneyError> {
+        RateProvider::gte(self, first, second)
+    }
+
+    pub fn gt(&self, first: Money, second: Money) -> Result<bool, MoneyError> {
+        RateProvider::gt(self, first, second)
+    }
+
+    pub fn clamp(&self, input: Money, min: Money, max: Money, output_currency: Currency) -> Result<Money, MoneyError> {
+        RateProvider::clamp(self, input, min, max, output_currency)
+    }
+}
+
+impl<P: RateProvider + ?Sized> RateProvider for &P {
+    fn rate(&self, from: Currency, to: Currency) -> Result<Decimal, MoneyError> {
+        (**self).rate(from, to)
+    }
+
+    fn rate_on(&self, date: NaiveDate, from: Currency, to: Currency) -> Result<Decimal, MoneyError> {
+        (**self).rate_on(date, from, to)
+    }
+
+    fn rounding_policy(&self) -> RoundingPolicy {
+        (**self).rounding_policy()
+    }
+}
+
+impl<P: RateProvider + ?Sized> RateProvider for Box<P> {
+    fn rate(&self, from: Currency, to: Currency) -> Result<Decimal, MoneyError> {
+        (**self).rate(from, to)
+    }
+
+    fn rate_on(&self, date: NaiveDate, from: Currency, to: Currency) -> Result<Decimal, MoneyError> {
+        (**self).rate_on(date, from, to)
+    }
+
+    fn rounding_policy(&self) -> RoundingPolicy {
+        (**self).rounding_policy()
+    }
+}
+
+/// Asks each of its providers in the order they were added and uses the first rate found, such
+/// as rates loaded from a file backed by a table of constants.
+#[derive(Default)]
+pub struct FallbackProvider<'a> {
+    providers: Vec<Box<dyn RateProvider + 'a>>,
+}
+
+impl<'a> FallbackProvider<'a> {
+    pub fn new() -> FallbackProvider<'a> {
+        FallbackProvider { providers: Vec::new() }
+    }
+
+    pub fn add_provider<P: RateProvider + 'a>(&mut self, provider: P) {
+        self.providers.push(Box::new(provider));
+    }
+
+    /// Returns the first rate found, or the last provider's error if none has one.
+    fn first_rate<F>(&self, lookup: F) -> Result<Decimal, MoneyError>
+    where
+        F: Fn(&dyn RateProvider) -> Result<Decimal, MoneyError>,
+    {
+        let mut error = MoneyError::CouldNotFindExchangeRate;
+        for provider in &self.providers {
+            match lookup(provider.as_ref()) {
+                Ok(rate) => return Ok(rate),
+                Err(provider_error) => error = provider_error,
+            }
+        }
+
+        Err(error)
+    }
+}
+
+impl<'a> RateProvider for FallbackProvider<'a> {
+    fn rate(&self, from: Currency, to: Currency) -> Result<Decimal, MoneyError> {
+        self.first_rate(|provider| provider.rate(from, to))
+    }
+
+    fn rate_on(&self, date: NaiveDate, from: Currency, to: Currency) -> Result<Decimal, MoneyError> {
+        self.first_rate(|provider| provider.rate_on(date, from, to))
+    }
+
+    /// Rounds like the first provider.
+    fn rounding_policy(&self) -> RoundingPolicy {
+        self.providers.first().map(|provider| provider.rounding_policy()).unwrap_or_default()
+    }
+}
+
+/// Remembers every rate its provider finds, for providers that are slow to ask. Failed lookups
+/// are not remembered.
+pub struct CachingProvider<P: RateProvider> {
+    provider: P,
+    rates: RwLock<HashMap<ExchangeRateQuery, Decimal>>,
+    dated_rates: RwLock<HashMap<(NaiveDate, ExchangeRateQuery), Decimal>>,
+}
+
+impl<P: RateProvider> CachingProvider<P> {
+    pub fn new(provider: P) -> CachingProvider<P> {
+        CachingProvider { provider, rates: RwLock::new(HashMap::new()), dated_rates: RwLock::new(HashMap::new()) }
+    }
+
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Forgets every remembered rate, such as after the provider's rates change.
+    pub fn clear(&self) {
+        self.rates.write().unwrap().clear();
+        self.dated_rates.write().unwrap().clear();
+    }
+}
+
+impl<P: RateProvider> RateProvider for CachingProvider<P> {
+    fn rate(&self, from: Currency, to: Currency) -> Result<Decimal, MoneyError> {
+        let key = ExchangeRateQuery { from, to };
+        if let Some(rate) = self.rates.read().unwrap().get(&key) {
+            return Ok(*rate);
+        }
+
+        let rate = self.provider.rate(from, to)?;
+        self.rates.write().unwrap().insert(key, rate);
+        Ok(rate)
+    }
+
+    fn rate_on(&self, date: NaiveDate, from: Currency, to: Currency) -> Result<Decimal, MoneyError> {
+        let key = (date, ExchangeRateQuery { from, to });
+        if let Some(rate) = self.dated_rates.read().unwrap().get(&key) {
+            return Ok(*rate);
+        }
+
+        let rate = self.provider.rate_on(date, from, to)?;
+        self.dated_rates.write().unwrap().insert(key, rate);
+        Ok(rate)
+    }
+
+    fn rounding_policy(&self) -> RoundingPolicy {
+        self.provider.rounding_policy()
+    }
+}
+
+/// Replaces its provider's rates for chosen pairs on every date, for what-if scenarios. Other
+/// pairs are left to the provider.
+pub struct OverrideProvider<P: RateProvider> {
+    provider: P,
+    overrides: HashMap<ExchangeRateQuery, Decimal>,
+}
+
+impl<P: RateProvider> OverrideProvider<P> {
+    pub fn new(provider: P) -> OverrideProvider<P> {
+        OverrideProvider { provider, overrides: HashMap::new() }
+    }
+
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    pub fn set_rate(&mut self, from: Currency, to: Currency, rate: Decimal) {
+        self.overrides.insert(ExchangeRateQuery { from, to }, rate);
+    }
+
+    /// Pins `rate` from `from` to `to` and its inverse the other way. The rate must be positive.
+    pub fn set_rate_and_inverse(&mut self, from: Currency, to: Currency, rate: Decimal) -> Result<(), MoneyError> {
+        if rate <= dec!(0) {
+            return Err(MoneyError::InvalidRate(Rate::from_fraction(rate)));
+        }
+
+        let inverse = dec!(1).checked_div(rate).ok_or(MoneyError::Overflow)?;
+        self.set_rate(from, to, rate);
+        self.set_rate(to, from, inverse);
+        Ok(())
+    }
+
+    pub fn remove_rate(&mut self, from: Currency, to: Currency) {
+        self.overrides.remove(&ExchangeRateQuery { from, to });
+    }
+}
+
+impl<P: RateProvider> RateProvider for OverrideProvider<P> {
+    fn rate(&self, from: Currency, to: Currency) -> Result<Decimal, MoneyError> {
+        match self.overrides.get(&ExchangeRateQuery { from, to }) {
+            Some(rate) => Ok(*rate),
+            None => self.provider.rate(from, to),
+        }
+    }
+
+    fn rate_on(&self, date: NaiveDate, from: Currency, to: Currency) -> Result<Decimal, MoneyError> {
+        match self.overrides.get(&ExchangeRateQuery { from, to }) {
+            Some(rate) => Ok(*rate),
+            None => self.provider.rate_on(date, from, to),
+        }
+    }
+
+    fn rounding_policy(&self) -> RoundingPolicy {
+        self.provider.rounding_policy()
+    }
+}
+
+/// Moves the value of chosen currencies against every other currency, for stress tests.
+///
+/// A shock of -10% on CAD makes one CAD buy 10% less of everything else, so a CAD to USD rate
+/// of 0.75 becomes 0.675 and a USD to CAD rate of 1.3 becomes 1.3 / 0.9. When both currencies
+/// of a pair are shocked, the two shocks combine.
+pub struct ShockProvider<P: RateProvider> {
+    provider: P,
+    shocks: HashMap<Currency, Rate>,
+}
+
+impl<P: RateProvider> ShockProvider<P> {
+    pub fn new(provider: P) -> ShockProvider<P> {
+        ShockProvider { provider, shocks: HashMap::new() }
+    }
+
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    pub fn shock(&self, currency: Currency) -> Rate {
+        self.shocks.get(&currency).copied().unwrap_or_default()
+    }
+
+    /// Changes the value of `currency` by `shock`. Shocks of -100% or less, which would leave the
+    /// currency worthless, are rejected.
+    pub fn set_shock(&mut self, currency: Currency, shock: Rate) -> Result<(), MoneyError> {
+        if shock.as_fraction() <= dec!(-1) {
+            return Err(MoneyError::InvalidRate(shock));
+        }
+
+        self.shocks.insert(currency, shock);
+        Ok(())
+    }
+
+    fn apply_shocks(&self, rate: Decimal, from: Currency, to: Currency) -> Result<Decimal, MoneyError> {
+        let from_value = dec!(1).checked_add(self.shock(from).as_fraction());
+        let to_value = dec!(1).checked_add(self.shock(to).as_fraction());
+        from_value
+            .zip(to_value)
+            .and_then(|(from_value, to_value)| rate.checked_mul(from_value)?.checked_div(to_value))
+            .ok_or(MoneyError::Overflow)
+    }
+}
+
+impl<P: RateProvider> RateProvider for ShockProvider<P> {
+    fn rate(&self, from: Currency, to: Currency) -> Result<Decimal, MoneyError> {
+        self.apply_shocks(self.provider.rate(from, to)?, from, to)
+    }
+
+    fn rate_on(&self, date: NaiveDate, from: Currency, to: Currency) -> Result<Decimal, MoneyError> {
+        self.apply_shocks(self.provider.rate_on(date, from, to)?, from, to)
+    }
+
+    fn rounding_policy(&self) -> RoundingPolicy {
+        self.provider.rounding_policy()
+    }
+}
+
+/// Answers every `rate` with another provider's rate on one date, so the conversion and
+/// comparison helpers can be used with dated rates, such as simulated paths loaded with
+/// `Exchange::load_fx_path`.
+pub struct DatedProvider<P: RateProvider> {
+    provider: P,
+    date: NaiveDate,
+}
+
+impl<P: RateProvider> DatedProvider<P> {
+    pub fn new(provider: P, date: NaiveDate) -> DatedProvider<P> {
+        DatedProvider { provider, date }
+    }
+
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+}
+
+impl<P: RateProvider> RateProvider for DatedProvider<P> {
+    fn rate(&self, from: Currency, to: Currency) -> Result<Decimal, MoneyError> {
+        self.provider.rate_on(self.date, from, to)
+    }
+
+    fn rate_on(&self, date: NaiveDate, from: Currency, to: Currency) -> Result<Decimal, MoneyError> {
+        self.provider.rate_on(date, from, to)
+    }
+
+    fn rounding_policy(&self) -> RoundingPolicy {
+        self.provider.rounding_policy()
+    }
+}
+
+impl Exchange {
+    /// The rates on `date`. `exchange.rates_on(date).lt(first, second)` compares the amounts at
+    /// that day's rates rather than the undated ones.
+    pub fn rates_on(&self, date: NaiveDate) -> DatedProvider<&Exchange> {
+        DatedProvider::new(self, date)
+    }
+}
@@ -0,0 +1,111 @@
+use crate::currency::{CurrencyData, Locale, ALL_CURRENCIES};
+use crate::{Currency, MoneyError};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::RwLock;
+
+/// The largest exponent a currency can have, since `Decimal` keeps at most 28 decimal places.
+const MAX_EXPONENT: u8 = 28;
+
+/// Definitions of the currencies added with `Currency::register`, in the order they were added.
+/// Entries are never removed, and their data is leaked so handles can keep a reference to it.
+static CUSTOM_CURRENCIES: RwLock<Vec<&'static CurrencyData>> = RwLock::new(Vec::new());
+
+/// A handle to a currency registered at runtime. See `Currency::register`.
+///
+/// The handle holds the currency's definition, so looking it up never takes the registry's lock.
+#[derive(Copy, Clone)]
+pub struct CustomCurrency {
+    index: usize,
+    data: &'static CurrencyData,
+}
+
+impl CustomCurrency {
+    fn new(index: usize, custom_currencies: &[&'static CurrencyData]) -> CustomCurrency {
+        CustomCurrency { index, data: custom_currencies[index] }
+    }
+
+    pub(crate) fn data(&self) -> CurrencyData {
+        *self.data
+    }
+
+    pub(crate) fn index(&self) -> usize {
+        self.index
+    }
+}
+
+// Every registered currency has its own index, so handles are compared by index alone.
+impl PartialEq for CustomCurrency {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl Eq for CustomCurrency {}
+
+impl Hash for CustomCurrency {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+    }
+}
+
+impl fmt::Debug for CustomCurrency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CustomCurrency").field(&self.data.code).finish()
+    }
+}
+
+impl Currency {
+    /// Defines a currency that is not part of ISO 4217, such as a cryptocurrency, a commodity
+    /// or loyalty points, and returns it for use anywhere an ISO currency is accepted.
+    ///
+    /// Codes are made of ASCII letters and digits, with at least one letter, and are stored in
+    /// upper case. Registering a code again with the same definition returns the existing
+    /// currency, while a different definition, or the code of an ISO currency, is an error.
+    /// Registered currencies are named by their code and last for the rest of the program.
+    pub fn register(code: &str, exponent: u8, symbol: &str, symbol_first: bool, locale: Locale) -> Result<Currency, MoneyError> {
+        let code = code.trim().to_ascii_uppercase();
+        if code.is_empty() || !code.chars().all(|c| c.is_ascii_alphanumeric()) || code.chars().all(|c| c.is_ascii_digit()) {
+            return Err(MoneyError::InvalidCurrencyDefinition(format!("{} is not a valid currency code", code)));
+        }
+        if exponent > MAX_EXPONENT {
+            return Err(MoneyError::InvalidCurrencyDefinition(format!("{} has more than {} decimal places", code, MAX_EXPONENT)));
+        }
+        if ALL_CURRENCIES.iter().any(|currency| currency.code() == code) {
+            return Err(MoneyError::CurrencyAlreadyDefined(code));
+        }
+
+        let mut custom_currencies = CUSTOM_CURRENCIES.write().unwrap();
+        if let Some(index) = custom_currencies.iter().position(|data| data.code == code) {
+            let existing = custom_currencies[index];
+            if existing.exponent == exponent && existing.symbol == symbol && existing.symbol_first == symbol_first && existing.locale == locale {
+                return Ok(Currency::Custom(CustomCurrency::new(index, &custom_currencies)));
+            }
+            return Err(MoneyError::CurrencyAlreadyDefined(code));
+        }
+
+        // The data is kept for the life of the program, like the ISO currencies' constants.
+        let code: &'static str = Box::leak(code.into_boxed_str());
+        custom_currencies.push(Box::leak(Box::new(CurrencyData {
+            code,
+            numeric_code: 0,
+            name: code,
+            exponent,
+            cash_increment: 1,
+            locale,
+            symbol: Box::leak(symbol.to_string().into_boxed_str()),
+            symbol_first,
+        })));
+        Ok(Currency::Custom(CustomCurrency::new(custom_currencies.len() - 1, &custom_currencies)))
+    }
+
+    /// Returns every currency registered with `Currency::register`, in the order they were added.
+    pub fn custom() -> Vec<Currency> {
+        let custom_currencies = CUSTOM_CURRENCIES.read().unwrap();
+        (0..custom_currencies.len()).map(|index| Currency::Custom(CustomCurrency::new(index, &custom_currencies))).collect()
+    }
+
+    pub fn is_custom(&self) -> bool {
+        matches!(self, Currency::Custom(_))
+    }
+}
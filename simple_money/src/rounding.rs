@@ -0,0 +1,38 @@
+use rust_decimal::{Decimal, RoundingStrategy};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// How an amount is brought back to a currency's minor unit after an operation such as a
+/// conversion leaves it with more decimal places than the currency allows.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RoundingPolicy {
+    /// Round to the nearest minor unit, with ties going to the even neighbour (banker's rounding).
+    #[default]
+    HalfEven,
+    /// Round to the nearest minor unit, with ties going away from zero.
+    HalfUp,
+    /// Round towards negative infinity.
+    Floor,
+    /// Round towards positive infinity.
+    Ceil,
+    /// Drop any digits beyond the minor unit.
+    Truncate,
+    /// Keep every decimal place produced by the operation.
+    FullPrecision,
+}
+
+impl RoundingPolicy {
+    pub fn round(&self, amount: Decimal, dp: u32) -> Decimal {
+        let strategy = match self {
+            RoundingPolicy::HalfEven => RoundingStrategy::MidpointNearestEven,
+            RoundingPolicy::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            RoundingPolicy::Floor => RoundingStrategy::ToNegativeInfinity,
+            RoundingPolicy::Ceil => RoundingStrategy::ToPositiveInfinity,
+            RoundingPolicy::Truncate => RoundingStrategy::ToZero,
+            RoundingPolicy::FullPrecision => return amount,
+        };
+
+        amount.round_dp_with_strategy(dp, strategy)
+    }
+}
@@ -0,0 +1,133 @@
+use crate::{ConversionCosts, Currency, Exchange, ExchangeRateQuery, RateLookupPolicy, RateMatrix, RoundingPolicy};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::de::{self, Deserializer};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+impl Serialize for Currency {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for Currency {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let code = String::deserialize(deserializer)?;
+        code.parse().map_err(de::Error::custom)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ExchangeRateEntry {
+    #[serde(flatten)]
+    query: ExchangeRateQuery,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    date: Option<NaiveDate>,
+    rate: Decimal,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ConversionCostsEntry {
+    #[serde(flatten)]
+    query: ExchangeRateQuery,
+    #[serde(flatten)]
+    costs: ConversionCosts,
+}
+
+#[derive(Serialize)]
+struct ExchangeRef<'a> {
+    rates: Vec<ExchangeRateEntry>,
+    rounding_policy: &'a RoundingPolicy,
+    rate_lookup_policy: &'a RateLookupPolicy,
+    costs: Vec<ConversionCostsEntry>,
+    pivot_currency: &'a Option<Currency>,
+    follow_inverse_rates: &'a bool,
+}
+
+#[derive(Deserialize)]
+struct ExchangeRepr {
+    rates: Vec<ExchangeRateEntry>,
+    #[serde(default)]
+    rounding_policy: RoundingPolicy,
+    #[serde(default)]
+    rate_lookup_policy: RateLookupPolicy,
+    #[serde(default)]
+    costs: Vec<ConversionCostsEntry>,
+    #[serde(default)]
+    pivot_currency: Option<Currency>,
+    #[serde(default = "follow_inverse_rates_by_default")]
+    follow_inverse_rates: bool,
+}
+
+fn follow_inverse_rates_by_default() -> bool {
+    true
+}
+
+impl Serialize for Exchange {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let undated_rates = self.rates.iter().map(|(from, to, rate)| ExchangeRateEntry { query: ExchangeRateQuery { from, to }, date: None, rate: *rate });
+        let dated_rates = self.dated_rates.iter().flat_map(|(from, to, history)| {
+            history.iter().map(move |(date, rate)| ExchangeRateEntry { query: ExchangeRateQuery { from, to }, date: Some(*date), rate: *rate })
+        });
+        let rates = undated_rates.chain(dated_rates).collect();
+        ExchangeRef {
+            rates,
+            rounding_policy: &self.rounding_policy,
+            rate_lookup_policy: &self.rate_lookup_policy,
+            costs: self.costs.iter().map(|(from, to, costs)| ConversionCostsEntry { query: ExchangeRateQuery { from, to }, costs: *costs }).collect(),
+            pivot_currency: &self.pivot_currency,
+            follow_inverse_rates: &self.follow_inverse_rates,
+        }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Exchange {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let repr = ExchangeRepr::deserialize(deserializer)?;
+        let mut rates: RateMatrix<Decimal> = RateMatrix::new();
+        let mut dated_rates: RateMatrix<BTreeMap<NaiveDate, Decimal>> = RateMatrix::new();
+        for entry in repr.rates {
+            let ExchangeRateQuery { from, to } = entry.query;
+            match entry.date {
+                Some(date) => {
+                    dated_rates.get_or_insert_with(from, to, BTreeMap::new).insert(date, entry.rate);
+                }
+                None => {
+                    rates.insert(from, to, entry.rate);
+                }
+            }
+        }
+
+        let mut exchange = Exchange {
+            rates,
+            dated_rates,
+            rounding_policy: repr.rounding_policy,
+            rate_lookup_policy: repr.rate_lookup_policy,
+            costs: RateMatrix::new(),
+            pivot_currency: repr.pivot_currency,
+            follow_inverse_rates: repr.follow_inverse_rates,
+            derived_rates: RwLock::new(RateMatrix::new()),
+        };
+        // Costs go through the same checks as when they are set in code.
+        for entry in repr.costs {
+            exchange.set_conversion_costs(entry.query.from, entry.query.to, entry.costs).map_err(de::Error::custom)?;
+        }
+
+        Ok(exchange)
+    }
+}
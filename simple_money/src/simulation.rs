@@ -0,0 +1,223 @@
+use crate::{Currency, Exchange, MoneyError, Rate};
+use chrono::{Duration, NaiveDate};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rand_distr::StandardNormal;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+
+/// Simulated rates are kept to this many significant digits, so that small rates keep their
+/// precision too.
+const RATE_SIGNIFICANT_DIGITS: i64 = 10;
+
+/// How a pair's rate moves from one step to the next. Drifts, volatilities and reversion speeds
+/// are annual.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum FxModel {
+    /// Geometric Brownian motion, where the rate is expected to grow by `drift` a year and its
+    /// log moves with `volatility`.
+    GeometricBrownianMotion { drift: Rate, volatility: Rate },
+    /// An Ornstein–Uhlenbeck process on the log of the rate, which pulls the rate back towards
+    /// `long_run_rate`. A `reversion_speed` of 1 closes about 63% of the gap in a year.
+    OrnsteinUhlenbeck { long_run_rate: Decimal, reversion_speed: Decimal, volatility: Rate },
+    /// Replays blocks of `block_length` consecutive changes from `history`, a series of rates
+    /// observed one step apart, picking each block at random. Keeping changes together in
+    /// blocks keeps some of the history's volatility clustering.
+    BlockBootstrap { history: Vec<Decimal>, block_length: usize },
+}
+
+/// Simulated rates for one pair, from the simulation's start date onwards.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct FxPath {
+    pub from: Currency,
+    pub to: Currency,
+    pub rates: BTreeMap<NaiveDate, Decimal>,
+}
+
+struct SimulatedPair {
+    from: Currency,
+    to: Currency,
+    initial_rate: Decimal,
+    model: FxModel,
+}
+
+/// Generates exchange rate paths for several pairs over the same dates.
+///
+/// Each pair moves independently of the others. The same seed always gives the same paths, and
+/// adding a pair does not change the paths of the pairs added before it.
+pub struct FxSimulation {
+    start: NaiveDate,
+    step: Duration,
+    steps: usize,
+    seed: u64,
+    pairs: Vec<SimulatedPair>,
+}
+
+impl FxSimulation {
+    /// A simulation starting on `start` and running for `steps` steps of `step`, which must be
+    /// at least a day.
+    pub fn new(start: NaiveDate, step: Duration, steps: usize, seed: u64) -> FxSimulation {
+        FxSimulation { start, step, steps, seed, pairs: Vec::new() }
+    }
+
+    /// Simulates the rate from `from` to `to`, starting at `initial_rate`.
+    pub fn add_pair(&mut self, from: Currency, to: Currency, initial_rate: Decimal, model: FxModel) -> Result<(), MoneyError> {
+        if from == to {
+            return Err(MoneyError::InvalidSimulation(format!("{} can't be simulated against itself", from)));
+        }
+        if initial_rate <= Decimal::new(0, 0) {
+            return Err(MoneyError::InvalidSimulation(format!("the initial {}/{} rate must be positive", from, to)));
+        }
+        match &model {
+            FxModel::GeometricBrownianMotion { volatility, .. } | FxModel::OrnsteinUhlenbeck { volatility, .. } if volatility.as_fraction().is_sign_negative() => {
+                return Err(MoneyError::InvalidSimulation(format!("the {}/{} volatility can't be negative", from, to)));
+            }
+            FxModel::OrnsteinUhlenbeck { long_run_rate, reversion_speed, .. } if *long_run_rate <= Decimal::new(0, 0) || reversion_speed.is_sign_negative() => {
+                return Err(MoneyError::InvalidSimulation(format!("the {}/{} long run rate must be positive and its reversion speed can't be negative", from, to)));
+            }
+            FxModel::BlockBootstrap { history, block_length } if *block_length == 0 || history.len() <= *block_length => {
+                return Err(MoneyError::InvalidSimulation(format!("the {}/{} history needs more rates than the block length, which must be at least 1", from, to)));
+            }
+            FxModel::BlockBootstrap { history, .. } if history.iter().any(|rate| *rate <= Decimal::new(0, 0)) => {
+                return Err(MoneyError::InvalidSimulation(format!("the {}/{} history can only hold positive rates", from, to)));
+            }
+            _ => {}
+        }
+
+        self.pairs.push(SimulatedPair { from, to, initial_rate, model });
+        Ok(())
+    }
+
+    /// Generates a path for every pair, in the order they were added.
+    ///
+    /// Fails with `MoneyError::InvalidSimulation` if a rate moves too far to be kept as a positive
+    /// `Decimal`.
+    pub fn generate(&self) -> Result<Vec<FxPath>, MoneyError> {
+        if self.step < Duration::days(1) {
+            return Err(MoneyError::InvalidSimulation("the step must be at least a day".to_string()));
+        }
+
+        let years_per_step = self.step.num_seconds() as f64 / Duration::days(365).num_seconds() as f64;
+        self.pairs
+            .iter()
+            .enumerate()
+            .map(|(index, pair)| {
+                let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+                rng.set_stream(index as u64);
+                self.generate_path(pair, &mut rng, years_per_step)
+            })
+            .collect()
+    }
+
+    /// Generates every path and loads it into `exchange` with `Exchange::load_fx_path`.
+    ///
+    /// The exchange's rates are checked with `Exchange::validate` first, and nothing is loaded if
+    /// they have any issues.
+    pub fn load_into(&self, exchange: &mut Exchange) -> Result<(), MoneyError> {
+        exchange.validate().into_result()?;
+        for path in self.generate()? {
+            exchange.load_fx_path(&path)?;
+        }
+
+        Ok(())
+    }
+
+    fn generate_path(&self, pair: &SimulatedPair, rng: &mut ChaCha8Rng, years_per_step: f64) -> Result<FxPath, MoneyError> {
+        let log_rates = match &pair.model {
+            FxModel::GeometricBrownianMotion { drift, volatility } => {
+                let drift = to_f64(drift.as_fraction())?;
+                let volatility = to_f64(volatility.as_fraction())?;
+                let mean_change = (drift - volatility * volatility / 2.0) * years_per_step;
+                let change_deviation = volatility * years_per_step.sqrt();
+                self.walk(to_f64(pair.initial_rate)?.ln(), |log_rate| {
+                    log_rate + mean_change + change_deviation * rng.sample::<f64, _>(StandardNormal)
+                })
+            }
+            FxModel::OrnsteinUhlenbeck { long_run_rate, reversion_speed, volatility } => {
+                let long_run_log_rate = to_f64(*long_run_rate)?.ln();
+                let reversion_speed = to_f64(*reversion_speed)?;
+                let volatility = to_f64(volatility.as_fraction())?;
+                // Uses the exact transition over a step, which stays stable for any step size.
+                let decay = (-reversion_speed * years_per_step).exp();
+                let deviation = if reversion_speed == 0.0 {
+                    volatility * years_per_step.sqrt()
+                } else {
+                    volatility * ((1.0 - decay * decay) / (2.0 * reversion_speed)).sqrt()
+                };
+                self.walk(to_f64(pair.initial_rate)?.ln(), |log_rate| {
+                    long_run_log_rate + (log_rate - long_run_log_rate) * decay + deviation * rng.sample::<f64, _>(StandardNormal)
+                })
+            }
+            FxModel::BlockBootstrap { history, block_length } => {
+                let log_history = history.iter().map(|rate| Ok(to_f64(*rate)?.ln())).collect::<Result<Vec<f64>, MoneyError>>()?;
+                let changes: Vec<f64> = log_history.windows(2).map(|pair| pair[1] - pair[0]).collect();
+                let mut block = changes.len()..changes.len();
+                self.walk(to_f64(pair.initial_rate)?.ln(), |log_rate| {
+                    if block.is_empty() {
+                        let block_start = rng.gen_range(0..=changes.len() - block_length);
+                        block = block_start..block_start + block_length;
+                    }
+                    log_rate + changes[block.next().unwrap()]
+                })
+            }
+        };
+
+        let mut rates = BTreeMap::new();
+        let mut date = self.start;
+        for (step, log_rate) in log_rates.into_iter().enumerate() {
+            if step > 0 {
+                date = date.checked_add_signed(self.step).ok_or(MoneyError::Overflow)?;
+            }
+            let rate = to_rate(log_rate.exp()).ok_or_else(|| {
+                MoneyError::InvalidSimulation(format!("the {}/{} rate on {} moved too far to be kept as a positive Decimal", pair.from, pair.to, date))
+            })?;
+            rates.insert(date, rate);
+        }
+        rates.insert(self.start, pair.initial_rate);
+
+        Ok(FxPath { from: pair.from, to: pair.to, rates })
+    }
+
+    /// Returns the log rate at the start and after each step.
+    fn walk<F: FnMut(f64) -> f64>(&self, initial_log_rate: f64, mut next: F) -> Vec<f64> {
+        let mut log_rates = Vec::with_capacity(self.steps + 1);
+        log_rates.push(initial_log_rate);
+        for _ in 0..self.steps {
+            log_rates.push(next(log_rates[log_rates.len() - 1]));
+        }
+        log_rates
+    }
+}
+
+/// Keeps a simulated rate to `RATE_SIGNIFICANT_DIGITS`, or returns `None` if it can't be kept as
+/// a positive `Decimal`.
+fn to_rate(rate: f64) -> Option<Decimal> {
+    if !rate.is_finite() || rate <= 0.0 {
+        return None;
+    }
+
+    let decimal_places = (RATE_SIGNIFICANT_DIGITS - 1 - rate.log10().floor() as i64).clamp(0, 28);
+    Decimal::from_f64(rate).map(|rate| rate.round_dp(decimal_places as u32)).filter(|rate| *rate > Decimal::new(0, 0))
+}
+
+fn to_f64(value: Decimal) -> Result<f64, MoneyError> {
+    value.to_f64().ok_or(MoneyError::Overflow)
+}
+
+impl Exchange {
+    /// Sets the rates along `path`, and their inverses, as dated rates. They are then used by
+    /// `convert_on`, `get_rate_on` and the helpers of `rates_on`.
+    ///
+    /// Every rate must be positive, and nothing is loaded if one isn't.
+    pub fn load_fx_path(&mut self, path: &FxPath) -> Result<(), MoneyError> {
+        if let Some(rate) = path.rates.values().find(|rate| **rate <= Decimal::new(0, 0)) {
+            return Err(MoneyError::InvalidRate(Rate::from_fraction(*rate)));
+        }
+
+        for (date, rate) in &path.rates {
+            self.set_rate_and_inverse_on(*date, path.from, path.to, *rate);
+        }
+        Ok(())
+    }
+}
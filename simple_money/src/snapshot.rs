@@ -0,0 +1,115 @@
+use crate::{Conversion, ConversionCosts, ConversionPath, Currency, DatedProvider, Exchange, FeeBreakdown, Money, MoneyError, RateLookupPolicy, RateMatrix, RateProvider, RoundingPolicy};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal_macros::*;
+use std::sync::RwLock;
+
+/// A read-only copy of an `Exchange`, made with `Exchange::snapshot`, for sharing between
+/// threads.
+///
+/// Every derived rate is worked out when the snapshot is taken, so lookups never take a lock or
+/// search the rate graph, and any number of threads can convert with one snapshot behind an
+/// `Arc`. Changes to the exchange afterwards do not affect the snapshot.
+///
+/// Taking a snapshot works out a path for every pair of currencies with rates, so take one
+/// after loading rates rather than before each conversion.
+pub struct ExchangeSnapshot {
+    exchange: Exchange,
+    paths: RateMatrix<ConversionPath>,
+}
+
+impl Exchange {
+    pub fn snapshot(&self) -> ExchangeSnapshot {
+        let exchange = Exchange {
+            rates: self.rates.clone(),
+            dated_rates: self.dated_rates.clone(),
+            rounding_policy: self.rounding_policy,
+            rate_lookup_policy: self.rate_lookup_policy,
+            costs: self.costs.clone(),
+            pivot_currency: self.pivot_currency,
+            follow_inverse_rates: self.follow_inverse_rates,
+            derived_rates: RwLock::new(RateMatrix::new()),
+        };
+        let paths = exchange.all_conversion_paths();
+        ExchangeSnapshot { exchange, paths }
+    }
+}
+
+impl ExchangeSnapshot {
+    pub fn rounding_policy(&self) -> RoundingPolicy {
+        self.exchange.rounding_policy()
+    }
+
+    pub fn rate_lookup_policy(&self) -> RateLookupPolicy {
+        self.exchange.rate_lookup_policy()
+    }
+
+    pub fn pivot_currency(&self) -> Option<Currency> {
+        self.exchange.pivot_currency()
+    }
+
+    pub fn conversion_costs(&self, from: Currency, to: Currency) -> ConversionCosts {
+        self.exchange.conversion_costs(from, to)
+    }
+
+    /// Returns the path `Exchange::conversion_path` found for the pair when the snapshot was
+    /// taken.
+    pub fn conversion_path(&self, from: Currency, to: Currency) -> Result<ConversionPath, MoneyError> {
+        if from == to {
+            return Ok(ConversionPath { currencies: vec![from], rate: dec!(1) });
+        }
+
+        self.paths.get(from, to).cloned().ok_or(MoneyError::CouldNotFindExchangeRate)
+    }
+
+    pub fn get_rate(&self, from: Currency, to: Currency) -> Result<Decimal, MoneyError> {
+        if from == to {
+            return Ok(dec!(1));
+        }
+
+        self.paths.get(from, to).map(|path| path.rate).ok_or(MoneyError::CouldNotFindExchangeRate)
+    }
+
+    /// Looks up the rate for `date` like `Exchange::get_rate_on`.
+    pub fn get_rate_on(&self, date: NaiveDate, from: Currency, to: Currency) -> Result<Decimal, MoneyError> {
+        self.exchange.get_rate_on_or_else(date, from, to, |from, to| self.get_rate(from, to))
+    }
+
+    /// Converts `money` like `Exchange::convert`, charging the pair's spread and fees.
+    pub fn convert(&self, money: Money, currency: Currency) -> Result<Conversion, MoneyError> {
+        if money.currency == currency {
+            return Ok(Conversion { converted: money, fees: FeeBreakdown::none(currency) });
+        }
+
+        let rate = self.get_rate(money.currency, currency)?;
+        self.exchange.convert_with_costs(money, currency, rate)
+    }
+
+    pub fn convert_on(&self, date: NaiveDate, money: Money, currency: Currency) -> Result<Conversion, MoneyError> {
+        if money.currency == currency {
+            return Ok(Conversion { converted: money, fees: FeeBreakdown::none(currency) });
+        }
+
+        let rate = self.get_rate_on(date, money.currency, currency)?;
+        self.exchange.convert_with_costs(money, currency, rate)
+    }
+
+    /// The rates on `date`, as with `Exchange::rates_on`.
+    pub fn rates_on(&self, date: NaiveDate) -> DatedProvider<&ExchangeSnapshot> {
+        DatedProvider::new(self, date)
+    }
+}
+
+impl RateProvider for ExchangeSnapshot {
+    fn rate(&self, from: Currency, to: Currency) -> Result<Decimal, MoneyError> {
+        self.get_rate(from, to)
+    }
+
+    fn rate_on(&self, date: NaiveDate, from: Currency, to: Currency) -> Result<Decimal, MoneyError> {
+        self.get_rate_on(date, from, to)
+    }
+
+    fn rounding_policy(&self) -> RoundingPolicy {
+        ExchangeSnapshot::rounding_policy(self)
+    }
+}
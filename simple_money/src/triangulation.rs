@@ -0,0 +1,191 @@
+use crate::{Currency, Exchange, MoneyError, RateMatrix};
+use rust_decimal::Decimal;
+use rust_decimal_macros::*;
+use std::collections::{HashMap, VecDeque};
+
+/// The currencies a conversion passes through, along with the combined rate from the first
+/// currency to the last.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ConversionPath {
+    pub currencies: Vec<Currency>,
+    pub rate: Decimal,
+}
+
+impl ConversionPath {
+    pub fn is_direct(&self) -> bool {
+        self.currencies.len() <= 2
+    }
+}
+
+impl Exchange {
+    /// Finds how `from` is converted into `to`.
+    ///
+    /// A rate set directly for the pair is always preferred. Otherwise the rate is derived from a
+    /// path through the rate graph, which also follows rates against the direction they were set
+    /// unless `set_follow_inverse_rates` is turned off. If a pivot currency is configured, the
+    /// path through the pivot is tried first, and the shortest path is used otherwise. Derived
+    /// rates are cached until the rates or the pivot change.
+    pub fn conversion_path(&self, from: Currency, to: Currency) -> Result<ConversionPath, MoneyError> {
+        if from == to {
+            return Ok(ConversionPath { currencies: vec![from], rate: dec!(1) });
+        }
+
+        if let Some(rate) = self.rates.get(from, to) {
+            return Ok(ConversionPath { currencies: vec![from, to], rate: *rate });
+        }
+
+        if let Some(path) = self.derived_rates.read().unwrap().get(from, to) {
+            return Ok(path.clone());
+        }
+
+        let path = self.find_path_through_pivot(from, to)
+            .or_else(|| self.find_shortest_path(&self.rate_graph(), from, to))
+            .ok_or(MoneyError::CouldNotFindExchangeRate)?;
+        self.derived_rates.write().unwrap().insert(from, to, path.clone());
+        Ok(path)
+    }
+
+    /// Finds the path between every two currencies that have rates, as `conversion_path` would,
+    /// leaving out pairs with no path.
+    pub(crate) fn all_conversion_paths(&self) -> RateMatrix<ConversionPath> {
+        let graph = self.rate_graph();
+        let mut paths = RateMatrix::new();
+        for from in self.rates.currencies() {
+            // One search from `from` finds the same shortest paths as searching for each `to`.
+            let previous = search_rate_graph(&graph, *from, None);
+            for to in self.rates.currencies() {
+                let path = match self.rates.get(*from, *to) {
+                    _ if from == to => Some(ConversionPath { currencies: vec![*from], rate: dec!(1) }),
+                    Some(rate) => Some(ConversionPath { currencies: vec![*from, *to], rate: *rate }),
+                    None => self.find_path_through_pivot(*from, *to).or_else(|| self.path_to(&previous, *to)),
+                };
+                if let Some(path) = path {
+                    paths.insert(*from, *to, path);
+                }
+            }
+        }
+
+        paths
+    }
+
+    pub fn pivot_currency(&self) -> Option<Currency> {
+        self.pivot_currency
+    }
+
+    /// Sets a currency that derived rates should be routed through when both legs are known.
+    pub fn set_pivot_currency(&mut self, pivot_currency: Option<Currency>) {
+        self.pivot_currency = pivot_currency;
+        self.clear_derived_rates();
+    }
+
+    pub fn follow_inverse_rates(&self) -> bool {
+        self.follow_inverse_rates
+    }
+
+    /// Sets whether a rate set for one direction of a pair can be inverted to convert the other
+    /// way, both for the pair itself and along derived paths. Defaults to `true`. When it is
+    /// turned off, `get_rate(CAD, USD)` fails if only the USD to CAD rate was set.
+    pub fn set_follow_inverse_rates(&mut self, follow_inverse_rates: bool) {
+        self.follow_inverse_rates = follow_inverse_rates;
+        self.clear_derived_rates();
+    }
+
+    pub(crate) fn clear_derived_rates(&mut self) {
+        self.derived_rates.write().unwrap().clear();
+    }
+
+    pub(crate) fn leg_rate(&self, from: Currency, to: Currency) -> Option<Decimal> {
+        if let Some(rate) = self.rates.get(from, to) {
+            return Some(*rate);
+        }
+        if !self.follow_inverse_rates {
+            return None;
+        }
+
+        self.rates
+            .get(to, from)
+            .filter(|rate| !rate.is_zero())
+            .map(|rate| dec!(1) / *rate)
+    }
+
+    fn path_from_currencies(&self, currencies: Vec<Currency>) -> Option<ConversionPath> {
+        let mut rate = dec!(1);
+        for leg in currencies.windows(2) {
+            rate = rate.checked_mul(self.leg_rate(leg[0], leg[1])?)?;
+        }
+
+        Some(ConversionPath { currencies, rate })
+    }
+
+    fn find_path_through_pivot(&self, from: Currency, to: Currency) -> Option<ConversionPath> {
+        let pivot = self.pivot_currency?;
+        if pivot == from || pivot == to {
+            return self.path_from_currencies(vec![from, to]);
+        }
+
+        self.path_from_currencies(vec![from, pivot, to])
+    }
+
+    /// Lists the currencies each currency can be converted into with one rate, in order of
+    /// their codes.
+    fn rate_graph(&self) -> HashMap<Currency, Vec<Currency>> {
+        let mut neighbours: HashMap<Currency, Vec<Currency>> = HashMap::new();
+        for (from, to, _) in self.rates.iter() {
+            neighbours.entry(from).or_default().push(to);
+            if self.follow_inverse_rates {
+                neighbours.entry(to).or_default().push(from);
+            }
+        }
+        for currencies in neighbours.values_mut() {
+            currencies.sort_by_key(|currency| currency.code());
+            currencies.dedup();
+        }
+
+        neighbours
+    }
+
+    fn find_shortest_path(&self, neighbours: &HashMap<Currency, Vec<Currency>>, from: Currency, to: Currency) -> Option<ConversionPath> {
+        let previous = search_rate_graph(neighbours, from, Some(to));
+        self.path_to(&previous, to)
+    }
+
+    /// Follows the currencies found by `search_rate_graph` back from `to` to where the search
+    /// started.
+    fn path_to(&self, previous: &HashMap<Currency, Currency>, to: Currency) -> Option<ConversionPath> {
+        if !previous.contains_key(&to) {
+            return None;
+        }
+
+        let mut currencies = vec![to];
+        let mut current = to;
+        while let Some(before) = previous.get(&current) {
+            currencies.push(*before);
+            current = *before;
+        }
+        currencies.reverse();
+        self.path_from_currencies(currencies)
+    }
+}
+
+/// Searches the rate graph breadth first from `from`, stopping once `to` is found, and returns
+/// the currency each currency was first reached from. Neighbours are visited in the order given,
+/// so the same search always finds the same paths.
+fn search_rate_graph(neighbours: &HashMap<Currency, Vec<Currency>>, from: Currency, to: Option<Currency>) -> HashMap<Currency, Currency> {
+    let mut previous: HashMap<Currency, Currency> = HashMap::new();
+    let mut queue = VecDeque::new();
+    queue.push_back(from);
+    while let Some(currency) = queue.pop_front() {
+        if Some(currency) == to {
+            break;
+        }
+
+        for neighbour in neighbours.get(&currency).into_iter().flatten() {
+            if *neighbour != from && !previous.contains_key(neighbour) {
+                previous.insert(*neighbour, currency);
+                queue.push_back(*neighbour);
+            }
+        }
+    }
+
+    previous
+}
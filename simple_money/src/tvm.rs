@@ -0,0 +1,222 @@
+//! Time value of money functions that match the spreadsheet `PV`, `FV`, `PMT`, `NPER` and
+//! `RATE` functions.
+//!
+//! As in a spreadsheet, money paid out is negative and money received is positive, rates are
+//! per period and a number of periods may be fractional. Amounts are not rounded; use
+//! `Money::round_to_minor_unit` on the results when needed.
+
+use crate::{Currency, Money, MoneyError, Rate};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, MathematicalOps};
+use rust_decimal_macros::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// How many Newton steps `rate` takes before giving up.
+const MAX_ITERATIONS: u32 = 100;
+
+/// When payments are made within each period.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PaymentTiming {
+    /// Payments are made at the end of each period, as with most loans. Spreadsheets call this
+    /// type 0.
+    #[default]
+    End,
+    /// Payments are made at the start of each period, as with rent. Spreadsheets call this
+    /// type 1.
+    Beginning,
+}
+
+impl PaymentTiming {
+    fn factor(&self) -> Decimal {
+        match self {
+            PaymentTiming::End => dec!(0),
+            PaymentTiming::Beginning => dec!(1),
+        }
+    }
+}
+
+/// Returns the currency shared by `amounts`.
+fn same_currency(amounts: &[Money]) -> Result<Currency, MoneyError> {
+    let currency = amounts[0].currency;
+    if amounts.iter().any(|amount| amount.currency != currency) {
+        return Err(MoneyError::MismatchedCurrencies);
+    }
+
+    Ok(currency)
+}
+
+fn add(first: Decimal, second: Decimal) -> Result<Decimal, MoneyError> {
+    first.checked_add(second).ok_or(MoneyError::Overflow)
+}
+
+fn sub(first: Decimal, second: Decimal) -> Result<Decimal, MoneyError> {
+    first.checked_sub(second).ok_or(MoneyError::Overflow)
+}
+
+fn mul(first: Decimal, second: Decimal) -> Result<Decimal, MoneyError> {
+    first.checked_mul(second).ok_or(MoneyError::Overflow)
+}
+
+/// Divides `dividend` by `divisor`, treating division by zero, which here only happens when a
+/// value is too small for `Decimal`, as an overflow.
+fn div(dividend: Decimal, divisor: Decimal) -> Result<Decimal, MoneyError> {
+    dividend.checked_div(divisor).ok_or(MoneyError::Overflow)
+}
+
+/// Returns `(1 + rate) ^ periods`.
+fn growth(rate: Decimal, periods: Decimal) -> Result<Decimal, MoneyError> {
+    let base = add(dec!(1), rate)?;
+    if !base.is_sign_positive() || base.is_zero() {
+        return Err(MoneyError::NoSolution);
+    }
+
+    let growth = if periods.fract().is_zero() {
+        periods.to_i64().and_then(|periods| base.checked_powi(periods))
+    } else {
+        base.checked_powd(periods)
+    };
+    growth.ok_or(MoneyError::Overflow)
+}
+
+/// Returns how much a payment of 1 each period adds up to by the last period.
+fn annuity_factor(rate: Decimal, periods: Decimal, timing: PaymentTiming, growth: Decimal) -> Result<Decimal, MoneyError> {
+    if rate.is_zero() {
+        return Ok(periods);
+    }
+
+    div(mul(add(dec!(1), mul(rate, timing.factor())?)?, sub(growth, dec!(1))?)?, rate)
+}
+
+/// Returns the future value of `present_value` plus `payment` each period, like the
+/// spreadsheet `FV` function.
+pub fn fv(rate: Rate, periods: Decimal, payment: Money, present_value: Money, timing: PaymentTiming) -> Result<Money, MoneyError> {
+    let currency = same_currency(&[payment, present_value])?;
+    let rate = rate.as_fraction();
+    let growth = growth(rate, periods)?;
+
+    let amount = -add(mul(present_value.amount, growth)?, mul(payment.amount, annuity_factor(rate, periods, timing, growth)?)?)?;
+    Ok(Money { amount, currency })
+}
+
+/// Returns the present value of `payment` each period plus `future_value` at the end, like
+/// the spreadsheet `PV` function.
+pub fn pv(rate: Rate, periods: Decimal, payment: Money, future_value: Money, timing: PaymentTiming) -> Result<Money, MoneyError> {
+    let currency = same_currency(&[payment, future_value])?;
+    let rate = rate.as_fraction();
+    let growth = growth(rate, periods)?;
+
+    let amount = div(-add(future_value.amount, mul(payment.amount, annuity_factor(rate, periods, timing, growth)?)?)?, growth)?;
+    Ok(Money { amount, currency })
+}
+
+/// Returns the payment each period that takes `present_value` to `future_value`, like the
+/// spreadsheet `PMT` function.
+pub fn pmt(rate: Rate, periods: Decimal, present_value: Money, future_value: Money, timing: PaymentTiming) -> Result<Money, MoneyError> {
+    let currency = same_currency(&[present_value, future_value])?;
+    let rate = rate.as_fraction();
+    let growth = growth(rate, periods)?;
+    let annuity_factor = annuity_factor(rate, periods, timing, growth)?;
+    if annuity_factor.is_zero() {
+        return Err(MoneyError::NoSolution);
+    }
+
+    let amount = div(-add(future_value.amount, mul(present_value.amount, growth)?)?, annuity_factor)?;
+    Ok(Money { amount, currency })
+}
+
+/// Returns how many periods of `payment` take `present_value` to `future_value`, like the
+/// spreadsheet `NPER` function.
+pub fn nper(rate: Rate, payment: Money, present_value: Money, future_value: Money, timing: PaymentTiming) -> Result<Decimal, MoneyError> {
+    same_currency(&[payment, present_value, future_value])?;
+    let rate = rate.as_fraction();
+    if rate.is_zero() {
+        if payment.amount.is_zero() {
+            return Err(MoneyError::NoSolution);
+        }
+        return div(-add(present_value.amount, future_value.amount)?, payment.amount);
+    }
+
+    let base = add(dec!(1), rate)?;
+    if !base.is_sign_positive() || base.is_zero() {
+        return Err(MoneyError::NoSolution);
+    }
+
+    // Solving pv * g + c * (g - 1) + fv = 0 for the growth g = (1 + rate) ^ periods.
+    let payments_value = div(mul(payment.amount, add(dec!(1), mul(rate, timing.factor())?)?)?, rate)?;
+    let denominator = add(present_value.amount, payments_value)?;
+    if denominator.is_zero() {
+        return Err(MoneyError::NoSolution);
+    }
+    let growth = div(sub(payments_value, future_value.amount)?, denominator)?;
+    if !growth.is_sign_positive() || growth.is_zero() {
+        return Err(MoneyError::NoSolution);
+    }
+
+    let growth = growth.checked_ln().ok_or(MoneyError::Overflow)?;
+    let base = base.checked_ln().ok_or(MoneyError::Overflow)?;
+    div(growth, base)
+}
+
+/// Returns the rate per period at which `payment` each period takes `present_value` to
+/// `future_value`, like the spreadsheet `RATE` function.
+///
+/// The rate is found with Newton's method starting from `guess`, or 10% when there is none.
+/// Cash flows with no rate, or with a rate the method can't reach from the guess, return
+/// `MoneyError::DidNotConverge`, as do steps too large or too small for `Decimal`.
+pub fn rate(periods: Decimal, payment: Money, present_value: Money, future_value: Money, timing: PaymentTiming, guess: Option<Rate>) -> Result<Rate, MoneyError> {
+    same_currency(&[payment, present_value, future_value])?;
+    let tolerance = dec!(0.0000000001);
+
+    let mut rate = guess.unwrap_or_else(|| Rate::from_percent(dec!(10))).as_fraction();
+    for _ in 0..MAX_ITERATIONS {
+        let next_rate = match next_newton_rate(rate, periods, payment.amount, present_value.amount, future_value.amount, timing) {
+            Ok(next_rate) => next_rate,
+            Err(_) => break,
+        };
+        if next_rate <= dec!(-1) {
+            break;
+        }
+        match next_rate.checked_sub(rate) {
+            Some(step) if step.abs() < tolerance => return Ok(Rate::from_fraction(next_rate)),
+            Some(_) => rate = next_rate,
+            None => break,
+        }
+    }
+
+    Err(MoneyError::DidNotConverge(MAX_ITERATIONS))
+}
+
+/// Takes one Newton step from `rate` towards the root of `rate_equation`.
+fn next_newton_rate(rate: Decimal, periods: Decimal, payment: Decimal, present_value: Decimal, future_value: Decimal, timing: PaymentTiming) -> Result<Decimal, MoneyError> {
+    let (value, slope) = rate_equation(rate, periods, payment, present_value, future_value, timing)?;
+    if slope.is_zero() {
+        return Err(MoneyError::DidNotConverge(MAX_ITERATIONS));
+    }
+
+    sub(rate, div(value, slope)?)
+}
+
+/// Returns the value of pv * g + pmt * (1 + rate * type) * (g - 1) / rate + fv at `rate`, which
+/// is zero at the solution, along with its derivative.
+fn rate_equation(rate: Decimal, periods: Decimal, payment: Decimal, present_value: Decimal, future_value: Decimal, timing: PaymentTiming) -> Result<(Decimal, Decimal), MoneyError> {
+    let timing = timing.factor();
+    if rate.is_zero() {
+        let value = add(add(present_value, mul(payment, periods)?)?, future_value)?;
+        let paid_periods = add(div(mul(periods, sub(periods, dec!(1))?)?, dec!(2))?, mul(timing, periods)?)?;
+        let slope = add(mul(periods, present_value)?, mul(payment, paid_periods)?)?;
+        return Ok((value, slope));
+    }
+
+    let growth = growth(rate, periods)?;
+    let previous_growth = div(growth, add(dec!(1), rate)?)?;
+    let timed_payment = mul(payment, add(dec!(1), mul(rate, timing)?)?)?;
+    let growth_gain = sub(growth, dec!(1))?;
+    let value = add(add(mul(present_value, growth)?, div(mul(timed_payment, growth_gain)?, rate)?)?, future_value)?;
+    let slope = add(
+        add(mul(mul(periods, present_value)?, previous_growth)?, div(mul(mul(payment, timing)?, growth_gain)?, rate)?)?,
+        div(mul(timed_payment, sub(mul(mul(periods, previous_growth)?, rate)?, growth_gain)?)?, mul(rate, rate)?)?,
+    )?;
+    Ok((value, slope))
+}
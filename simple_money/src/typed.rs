@@ -0,0 +1,237 @@
+use crate::{Currency, Exchange, Money, MoneyError, Rate};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::Sum;
+use std::marker::PhantomData;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A currency known at compile time, used as the type parameter of `TypedMoney`.
+///
+/// Every currency in `Currency::all` has a marker type in this module named after its code,
+/// such as `Cad` for `Currency::CAD`. Implement this trait on your own type to use a custom
+/// currency.
+pub trait CurrencyType {
+    fn currency() -> Currency;
+}
+
+macro_rules! currency_types {
+    ($($name:ident => $currency:ident),* $(,)?) => {
+        $(
+            #[doc = concat!("The `CurrencyType` for `Currency::", stringify!($currency), "`.")]
+            #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+            pub enum $name {}
+
+            impl CurrencyType for $name {
+                fn currency() -> Currency {
+                    Currency::$currency
+                }
+            }
+        )*
+    };
+}
+
+// A `currency_types!` entry for each row of data/currencies.csv, generated by build.rs.
+include!(concat!(env!("OUT_DIR"), "/currency_types.rs"));
+
+/// An amount whose currency is part of its type, so that adding, subtracting or comparing
+/// amounts in different currencies does not compile.
+///
+/// It has the same layout as a `Decimal`, and converting to and from `Money` only adds or
+/// checks the currency.
+///
+/// ```compile_fail
+/// use rust_decimal::Decimal;
+/// use simple_money::typed::{Cad, Usd};
+/// use simple_money::TypedMoney;
+///
+/// let total = TypedMoney::<Cad>::new(Decimal::new(1, 0)) + TypedMoney::<Usd>::new(Decimal::new(1, 0));
+/// ```
+#[repr(transparent)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(into = "Money", try_from = "Money", bound = "C: CurrencyType"))]
+pub struct TypedMoney<C: CurrencyType> {
+    amount: Decimal,
+    currency: PhantomData<C>,
+}
+
+impl<C: CurrencyType> TypedMoney<C> {
+    pub fn new(amount: Decimal) -> TypedMoney<C> {
+        TypedMoney { amount, currency: PhantomData }
+    }
+
+    pub fn zero() -> TypedMoney<C> {
+        TypedMoney::new(Decimal::new(0, 0))
+    }
+
+    pub fn amount(&self) -> Decimal {
+        self.amount
+    }
+
+    pub fn currency(&self) -> Currency {
+        C::currency()
+    }
+
+    pub fn to_money(&self) -> Money {
+        Money { amount: self.amount, currency: C::currency() }
+    }
+}
+
+impl<C: CurrencyType> From<TypedMoney<C>> for Money {
+    fn from(money: TypedMoney<C>) -> Money {
+        money.to_money()
+    }
+}
+
+impl<C: CurrencyType> TryFrom<Money> for TypedMoney<C> {
+    type Error = MoneyError;
+
+    fn try_from(money: Money) -> Result<TypedMoney<C>, MoneyError> {
+        if money.currency != C::currency() {
+            return Err(MoneyError::MismatchedCurrencies);
+        }
+
+        Ok(TypedMoney::new(money.amount))
+    }
+}
+
+// Implemented by hand rather than derived so that the marker type needs no bounds of its own.
+impl<C: CurrencyType> Clone for TypedMoney<C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C: CurrencyType> Copy for TypedMoney<C> {}
+
+impl<C: CurrencyType> PartialEq for TypedMoney<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.amount == other.amount
+    }
+}
+
+impl<C: CurrencyType> Eq for TypedMoney<C> {}
+
+impl<C: CurrencyType> PartialOrd for TypedMoney<C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<C: CurrencyType> Ord for TypedMoney<C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.amount.cmp(&other.amount)
+    }
+}
+
+impl<C: CurrencyType> Hash for TypedMoney<C> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.amount.hash(state);
+    }
+}
+
+impl<C: CurrencyType> Default for TypedMoney<C> {
+    fn default() -> Self {
+        TypedMoney::zero()
+    }
+}
+
+impl<C: CurrencyType> fmt::Debug for TypedMoney<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedMoney").field("amount", &self.amount).field("currency", &C::currency()).finish()
+    }
+}
+
+impl<C: CurrencyType> fmt::Display for TypedMoney<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.to_money().fmt(f)
+    }
+}
+
+impl<C: CurrencyType> Add for TypedMoney<C> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        TypedMoney::new(self.amount + other.amount)
+    }
+}
+
+impl<C: CurrencyType> AddAssign for TypedMoney<C> {
+    fn add_assign(&mut self, other: Self) {
+        self.amount += other.amount;
+    }
+}
+
+impl<C: CurrencyType> Sub for TypedMoney<C> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        TypedMoney::new(self.amount - other.amount)
+    }
+}
+
+impl<C: CurrencyType> SubAssign for TypedMoney<C> {
+    fn sub_assign(&mut self, other: Self) {
+        self.amount -= other.amount;
+    }
+}
+
+impl<C: CurrencyType> Neg for TypedMoney<C> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        TypedMoney::new(-self.amount)
+    }
+}
+
+impl<C: CurrencyType> Mul<Decimal> for TypedMoney<C> {
+    type Output = Self;
+
+    fn mul(self, other: Decimal) -> Self {
+        TypedMoney::new(self.amount * other)
+    }
+}
+
+impl<C: CurrencyType> Mul<Rate> for TypedMoney<C> {
+    type Output = Self;
+
+    fn mul(self, rate: Rate) -> Self {
+        TypedMoney::new(self.amount * rate.as_fraction())
+    }
+}
+
+impl<C: CurrencyType> Div<Decimal> for TypedMoney<C> {
+    type Output = Self;
+
+    fn div(self, other: Decimal) -> Self {
+        TypedMoney::new(self.amount / other)
+    }
+}
+
+impl<C: CurrencyType> Sum for TypedMoney<C> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(TypedMoney::zero(), |total, money| total + money)
+    }
+}
+
+impl Exchange {
+    /// Converts `money` like `convert`, charging the pair's spread and fees, and returns the
+    /// amount received in the target type.
+    pub fn convert_typed<Source: CurrencyType, Target: CurrencyType>(&self, money: TypedMoney<Source>) -> Result<TypedMoney<Target>, MoneyError> {
+        let conversion = self.convert(money.to_money(), Target::currency())?;
+        TypedMoney::try_from(conversion.converted)
+    }
+
+    pub fn convert_typed_on<Source: CurrencyType, Target: CurrencyType>(&self, date: NaiveDate, money: TypedMoney<Source>) -> Result<TypedMoney<Target>, MoneyError> {
+        let conversion = self.convert_on(date, money.to_money(), Target::currency())?;
+        TypedMoney::try_from(conversion.converted)
+    }
+
+    pub fn convert_typed_at_mid_rate<Source: CurrencyType, Target: CurrencyType>(&self, money: TypedMoney<Source>) -> Result<TypedMoney<Target>, MoneyError> {
+        TypedMoney::try_from(self.convert_at_mid_rate(money.to_money(), Target::currency())?)
+    }
+}
@@ -0,0 +1,176 @@
+use crate::{Currency, Exchange, MoneyError, Rate};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal_macros::*;
+use std::fmt;
+
+/// What `Exchange::validate_with` checks beyond the rates themselves.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ValidationOptions {
+    /// How far a rate and its inverse, or a round trip through three currencies, may stray from
+    /// breaking even before it is reported. Defaults to 1 basis point.
+    pub tolerance: Rate,
+    /// Currencies that must all be convertible into each other, directly or by triangulation.
+    pub required_currencies: Vec<Currency>,
+}
+
+impl Default for ValidationOptions {
+    fn default() -> ValidationOptions {
+        ValidationOptions { tolerance: Rate::from_basis_points(dec!(1)), required_currencies: Vec::new() }
+    }
+}
+
+/// A problem found by `Exchange::validate`.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum RateIssue {
+    /// A rate of zero or less. `date` is `None` for rates set with `set_rate`.
+    NonPositiveRate { from: Currency, to: Currency, date: Option<NaiveDate>, rate: Decimal },
+    /// A rate and the rate set for the opposite direction that do not multiply to 1 within the
+    /// tolerance.
+    InconsistentInverse { from: Currency, to: Currency, date: Option<NaiveDate>, rate: Decimal, inverse_rate: Decimal },
+    /// Converting around `currencies`, which starts and ends with the same currency, returns
+    /// more than was converted by `gain`.
+    ArbitrageCycle { currencies: Vec<Currency>, gain: Rate },
+    /// A pair of required currencies with no direct or triangulated rate.
+    MissingPair { from: Currency, to: Currency },
+}
+
+impl fmt::Display for RateIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RateIssue::NonPositiveRate { from, to, date: Some(date), rate } => write!(f, "the {}/{} rate on {} is {}", from, to, date, rate),
+            RateIssue::NonPositiveRate { from, to, date: None, rate } => write!(f, "the {}/{} rate is {}", from, to, rate),
+            RateIssue::InconsistentInverse { from, to, date: Some(date), rate, inverse_rate } => {
+                write!(f, "the {}/{} rate of {} on {} does not match its inverse of {}", from, to, rate, date, inverse_rate)
+            }
+            RateIssue::InconsistentInverse { from, to, date: None, rate, inverse_rate } => {
+                write!(f, "the {}/{} rate of {} does not match its inverse of {}", from, to, rate, inverse_rate)
+            }
+            RateIssue::ArbitrageCycle { currencies, gain } => {
+                let codes: Vec<&str> = currencies.iter().map(|currency| currency.code()).collect();
+                write!(f, "converting {} gains {}", codes.join(" to "), gain)
+            }
+            RateIssue::MissingPair { from, to } => write!(f, "there is no rate from {} to {}", from, to),
+        }
+    }
+}
+
+/// Everything `Exchange::validate` found wrong with an exchange's rates.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct ValidationReport {
+    issues: Vec<RateIssue>,
+}
+
+impl ValidationReport {
+    pub fn issues(&self) -> &[RateIssue] {
+        &self.issues
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Turns a report with any issues into `MoneyError::InvalidRates`, so that code which needs
+    /// sound rates can refuse to go on with `?`.
+    pub fn into_result(self) -> Result<(), MoneyError> {
+        if self.is_valid() {
+            return Ok(());
+        }
+
+        Err(MoneyError::InvalidRates(self))
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_valid() {
+            return write!(f, "no issues");
+        }
+
+        let issues: Vec<String> = self.issues.iter().map(RateIssue::to_string).collect();
+        write!(f, "{}", issues.join("; "))
+    }
+}
+
+impl Exchange {
+    /// Checks the rates with the default `ValidationOptions`. See `validate_with`.
+    pub fn validate(&self) -> ValidationReport {
+        self.validate_with(&ValidationOptions::default())
+    }
+
+    /// Looks for likely data entry mistakes in the rates:
+    ///
+    /// - rates of zero or less, undated or dated;
+    /// - pairs whose rates in both directions, set for the same date or both undated, are not
+    ///   each other's inverse within the tolerance;
+    /// - round trips through three currencies at the undated rates that gain more than the
+    ///   tolerance, where a pair set in only one direction is used backwards at its inverse;
+    /// - pairs of `required_currencies` that cannot be converted.
+    pub fn validate_with(&self, options: &ValidationOptions) -> ValidationReport {
+        let tolerance = options.tolerance.as_fraction().abs();
+        let mut issues = Vec::new();
+
+        let undated_rates = self.rates.iter().map(|(from, to, rate)| (from, to, None, *rate));
+        let dated_rates = self.dated_rates.iter().flat_map(|(from, to, history)| history.iter().map(move |(date, rate)| (from, to, Some(*date), *rate)));
+        let all_rates: Vec<(Currency, Currency, Option<NaiveDate>, Decimal)> = undated_rates.chain(dated_rates).collect();
+
+        for (from, to, date, rate) in &all_rates {
+            if *rate <= dec!(0) {
+                issues.push(RateIssue::NonPositiveRate { from: *from, to: *to, date: *date, rate: *rate });
+            }
+        }
+
+        for (from, to, date, rate) in &all_rates {
+            if from.index() > to.index() || *rate <= dec!(0) {
+                continue;
+            }
+            let inverse_rate = match date {
+                Some(date) => self.dated_rates.get(*to, *from).and_then(|history| history.get(date)),
+                None => self.rates.get(*to, *from),
+            };
+            if let Some(inverse_rate) = inverse_rate.filter(|inverse_rate| **inverse_rate > dec!(0)) {
+                let round_trip = rate.checked_mul(*inverse_rate);
+                if !matches!(round_trip, Some(round_trip) if (round_trip - dec!(1)).abs() <= tolerance) {
+                    issues.push(RateIssue::InconsistentInverse { from: *from, to: *to, date: *date, rate: *rate, inverse_rate: *inverse_rate });
+                }
+            }
+        }
+
+        let currencies = self.rates.currencies();
+        for (first_position, first) in currencies.iter().enumerate() {
+            for (second_position, second) in currencies.iter().enumerate().skip(first_position + 1) {
+                for third in currencies.iter().skip(second_position + 1) {
+                    for cycle in [[*first, *second, *third, *first], [*first, *third, *second, *first]] {
+                        if let Some(gain) = self.cycle_gain(&cycle) {
+                            if gain > tolerance {
+                                issues.push(RateIssue::ArbitrageCycle { currencies: cycle.to_vec(), gain: Rate::from_fraction(gain) });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        for from in &options.required_currencies {
+            for to in &options.required_currencies {
+                if from != to && self.conversion_path(*from, *to).is_err() {
+                    issues.push(RateIssue::MissingPair { from: *from, to: *to });
+                }
+            }
+        }
+
+        ValidationReport { issues }
+    }
+
+    /// Returns what converting around `cycle` gains as a fraction, or `None` if a leg has no
+    /// positive rate.
+    fn cycle_gain(&self, cycle: &[Currency]) -> Option<Decimal> {
+        let mut product = dec!(1);
+        for leg in cycle.windows(2) {
+            let rate = self.leg_rate(leg[0], leg[1]).filter(|rate| *rate > dec!(0))?;
+            product = product.checked_mul(rate)?;
+        }
+
+        Some(product - dec!(1))
+    }
+}